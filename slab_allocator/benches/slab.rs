@@ -0,0 +1,135 @@
+use std::alloc::Layout;
+use std::hint::black_box;
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+use slab_allocator::{CacheHint, Slab, SlabCache};
+
+const ROUNDS: usize = 2_000;
+const BATCH: usize = 48;
+
+fn report(name: &str, elapsed: Duration, ops: usize) {
+    let per_op = elapsed.as_nanos() as f64 / ops as f64;
+    println!("{name:<32} {elapsed:>12?} {per_op:>8.2} ns/op");
+}
+
+/// Allocates a batch, touches every object, frees half of it and refills,
+/// which is where LIFO and FIFO reuse orders diverge.
+fn bench_ordering(name: &str, hint: CacheHint) {
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    let mut cache = SlabCache::new();
+    let mut ptrs: Vec<NonNull<u8>> = Vec::with_capacity(BATCH);
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        while ptrs.len() < BATCH {
+            let ptr = cache.allocate_with_hint(layout, hint).unwrap();
+            unsafe { ptr.as_ptr().write_bytes(0xA5, layout.size()) };
+            ptrs.push(ptr);
+        }
+        for ptr in ptrs.drain(BATCH / 2..) {
+            cache.deallocate(black_box(ptr), layout);
+        }
+    }
+    let elapsed = start.elapsed();
+
+    for ptr in ptrs {
+        cache.deallocate(ptr, layout);
+    }
+    report(name, elapsed, ROUNDS * BATCH);
+}
+
+/// Writes to every object of `slabs` in a scattered order. With 64-byte
+/// objects spread over thousands of pages the walk is bound by TLB misses,
+/// so the gap between regular and huge pages approximates the TLB saving.
+#[cfg(feature = "huge_pages")]
+fn bench_tlb_walk(name: &str, slabs: &mut [Slab]) {
+    let mut ptrs: Vec<NonNull<u8>> = Vec::new();
+    for slab in slabs.iter_mut() {
+        while let Some(ptr) = slab.allocate() {
+            ptrs.push(ptr);
+        }
+    }
+
+    let len = ptrs.len();
+    let mut index = 0;
+    let start = Instant::now();
+    for _ in 0..len * 8 {
+        index = (index + 7919) % len;
+        unsafe { black_box(ptrs[index].as_ptr()).write_volatile(index as u8) };
+    }
+    let elapsed = start.elapsed();
+
+    let mut ptrs = ptrs.into_iter();
+    for slab in slabs.iter_mut() {
+        for ptr in ptrs.by_ref().take(slab.stats().allocated) {
+            slab.deallocate(ptr);
+        }
+    }
+    report(name, elapsed, len * 8);
+}
+
+/// Keeps the first object of many slabs hot. Uncoloured, every object sits
+/// at the same page offset and competes for one cache set; staggering the
+/// slabs by a cache line each spreads them over `colors` sets.
+fn bench_coloring(name: &str, colors: usize) {
+    let mut slabs: Vec<Slab> = (0..256)
+        .map(|index| Slab::new_with_color(64, index % colors * 64).unwrap())
+        .collect();
+    let ptrs: Vec<NonNull<u8>> = slabs.iter_mut().map(|slab| slab.allocate().unwrap()).collect();
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        for ptr in &ptrs {
+            unsafe { black_box(ptr.as_ptr()).write_volatile(round as u8) };
+        }
+    }
+    let elapsed = start.elapsed();
+
+    for (slab, ptr) in slabs.iter_mut().zip(ptrs) {
+        slab.deallocate(ptr);
+    }
+    report(name, elapsed, ROUNDS * 256);
+}
+
+/// Serves 10 000 allocations per round in bursts of 1 000, the most one
+/// tier holds, either resetting one cache between bursts or dropping it and
+/// starting from a fresh one.
+fn bench_reset(name: &str, rounds: usize, clear: bool) {
+    const OBJECTS: usize = 10_000;
+    const BURST: usize = 1_000;
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    let mut cache = SlabCache::new();
+
+    let start = Instant::now();
+    for _ in 0..rounds * OBJECTS / BURST {
+        for _ in 0..BURST {
+            black_box(cache.allocate(layout).unwrap());
+        }
+        if clear {
+            cache.clear();
+        } else {
+            cache = SlabCache::new();
+        }
+    }
+    let elapsed = start.elapsed();
+    report(name, elapsed, rounds * OBJECTS);
+}
+
+fn main() {
+    bench_ordering("ordering/lifo", CacheHint::Lifo);
+    bench_ordering("ordering/fifo", CacheHint::Fifo);
+    bench_coloring("coloring/none", 1);
+    bench_coloring("coloring/16_colors", 16);
+    bench_reset("reset/clear", 100, true);
+    bench_reset("reset/drop_and_recreate", 100, false);
+
+    #[cfg(feature = "huge_pages")]
+    {
+        let pages = 16;
+        let mut regular: Vec<Slab> = (0..pages * 512).map(|_| Slab::new(64).unwrap()).collect();
+        bench_tlb_walk("tlb/regular_pages", &mut regular);
+        let mut huge = vec![Slab::new_huge(64, pages).unwrap()];
+        bench_tlb_walk("tlb/huge_pages", &mut huge);
+    }
+}
@@ -1,346 +1,6226 @@
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(allocator_api))]
 
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
 
 use core::alloc::{GlobalAlloc, Layout};
-use core::ptr::NonNull;
+#[cfg(feature = "nightly")]
+use core::alloc::{AllocError, Allocator};
+use core::cell::RefCell;
+use core::cmp::Reverse;
+use core::fmt;
+use core::ptr::{self, NonNull};
+use core::iter::{self, FusedIterator};
 use core::mem;
-use alloc::alloc::{alloc, dealloc};
+use lock::Lock;
+use core::slice;
+#[cfg(any(feature = "thread_cache", feature = "alloc_counter"))]
+use core::sync::atomic::Ordering;
+#[cfg(feature = "thread_cache")]
+use core::sync::atomic::AtomicUsize;
+#[cfg(feature = "alloc_counter")]
+use core::sync::atomic::AtomicU64;
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, realloc};
+#[cfg(feature = "fresh_zeroed")]
+use alloc::alloc::alloc_zeroed;
+#[cfg(feature = "nightly")]
+use alloc::boxed::Box;
+#[cfg(feature = "ttl")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "nightly")]
+use core::pin::Pin;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(all(
+    target_os = "linux",
+    any(feature = "numa", feature = "huge_pages", feature = "std", feature = "guard_pages")
+))]
+mod sys;
+pub mod lock;
+#[cfg(feature = "thread_cache")]
+mod thread_cache;
 
 const SLAB_SIZE: usize = 4096;
+const PAGE_SIZE: usize = 4096;
+const MAX_SLAB_SIZE: usize = 64 * PAGE_SIZE;
+#[cfg(feature = "huge_pages")]
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
 const MAX_OBJECT_SIZE: usize = 512;
+const CACHE_LINE_SIZE: usize = 64;
+/// Alignment every slot is rounded to: a word, or 16 bytes with `align16`
+/// for SIMD vectors and 128-bit atomics. The `offset_free_list` feature
+/// trades it for denser slots.
+const MIN_ALIGN: usize = if cfg!(feature = "align16") { 16 } else { 8 };
+/// Heap backing regions start on a `SLAB_SIZE` boundary, so masking an
+/// object address with `!(SLAB_SIZE - 1)` yields the base of a 4 KiB slab.
+const BACKING_ALIGN: usize = SLAB_SIZE;
+const MAX_SLABS: usize = 16;
+/// Most empty slabs an allocator keeps aside for reuse.
+const MAX_RETAINED_SLABS: usize = 4;
+const TINY_OBJECT_SIZE: usize = 8;
+const SMALL_OBJECT_SIZE: usize = 64;
+const MEDIUM_OBJECT_SIZE: usize = 256;
+const LARGE_OBJECT_SIZE: usize = 512;
 
-struct FreeNode {
+/// Link stored at the start of every free slot. Opaque: it only appears as
+/// the free-list head handed over by [`Slab::into_raw_parts`].
+#[cfg(not(feature = "offset_free_list"))]
+pub struct FreeNode {
     next: Option<NonNull<FreeNode>>,
 }
 
+/// With `offset_free_list` a free slot only stores the index of the next
+/// free slot, relative to the first slot of the slab.
+#[cfg(feature = "offset_free_list")]
+pub struct FreeNode {
+    next: u16,
+}
+
+/// End of an offset free list; also bounds the slots such a slab can track.
+#[cfg(feature = "offset_free_list")]
+const NULL_OFFSET: u16 = u16::MAX;
+
+/// Order in which freed slots are handed out again.
+///
+/// `Lifo` reuses the most recently freed slot first, `Fifo` the least
+/// recently freed one. `Default` keeps the historical LIFO behaviour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheHint {
+    Lifo,
+    Fifo,
+    #[default]
+    Default,
+}
+
+/// Order in which a fresh slab hands out its slots.
+///
+/// `Strided(n)` walks every `n`-th slot, then starts over one slot further,
+/// so consecutive allocations land `n` slots apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InitOrder {
+    #[default]
+    Ascending,
+    Descending,
+    Strided(usize),
+}
+
+/// Power-of-two bucket of an object size, from `Tiny` (1-8 bytes) through
+/// `Small` (9-64), `Medium` (65-256) and `Large` (257-512).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SizeClass {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+}
+
+impl SizeClass {
+    /// Class of `size` bytes; `None` for zero and sizes past `MAX_OBJECT_SIZE`.
+    pub const fn for_size(size: usize) -> Option<Self> {
+        match size {
+            0 => None,
+            1..=8 => Some(SizeClass::Tiny),
+            9..=64 => Some(SizeClass::Small),
+            65..=256 => Some(SizeClass::Medium),
+            257..=512 => Some(SizeClass::Large),
+            _ => None,
+        }
+    }
+
+    /// Alignment worth requesting for objects of the class: a word for tiny
+    /// objects, 16 bytes for small ones, a cache line above that.
+    pub const fn align_for_class(self) -> usize {
+        match self {
+            SizeClass::Tiny => mem::align_of::<usize>(),
+            SizeClass::Small => 16,
+            SizeClass::Medium | SizeClass::Large => CACHE_LINE_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlabStats {
+    /// Effective slot size after rounding.
+    pub object_size: usize,
+    /// Size originally asked for by the caller.
+    pub requested_size: usize,
+    pub capacity: usize,
+    pub allocated: usize,
+    pub free_count: usize,
+    /// `allocated * object_size`.
+    pub bytes_allocated: usize,
+    /// `capacity * object_size`.
+    pub bytes_capacity: usize,
+}
+
+/// Outcome of [`SlabAllocator::compact`] and [`SlabAllocator::compact_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Live objects copied into another slab.
+    pub objects_moved: usize,
+    /// Slabs released once empty.
+    pub slabs_freed: usize,
+    /// Backing bytes handed back with those slabs.
+    pub bytes_reclaimed: usize,
+}
+
+/// Call counters kept by a [`SlabAllocator`].
+///
+/// `alloc_hits` counts allocations served by an existing slab, `alloc_misses`
+/// those that had to create a new one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocCounters {
+    pub alloc_calls: usize,
+    pub alloc_hits: usize,
+    pub alloc_misses: usize,
+    pub free_calls: usize,
+}
+
+/// Returned when a size or [`Layout`] cannot be served by a slab: zero,
+/// larger than `MAX_OBJECT_SIZE`, or aligned beyond a `usize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutToSlabError;
+
+impl fmt::Display for LayoutToSlabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("layout cannot be served by a slab")
+    }
+}
+
+/// Why a [`SlabAllocator`] could not provide slab memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlabError {
+    /// Every one of the `MAX_SLABS` slots already holds a slab.
+    SlabArrayFull,
+    /// The backing memory for a new slab could not be obtained.
+    OutOfMemory,
+    /// The object size cannot be served by any slab.
+    InvalidObjectSize,
+    /// Allocators or slabs built for different object sizes were combined.
+    ObjectSizeMismatch,
+    /// Only `moved` slabs could be transferred before the slab array filled up.
+    PartialMerge { moved: usize },
+}
+
+impl fmt::Display for SlabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SlabError::SlabArrayFull => "no free slab slot left",
+            SlabError::OutOfMemory => "slab backing allocation failed",
+            SlabError::InvalidObjectSize => "object size cannot be served by a slab",
+            SlabError::ObjectSizeMismatch => "object sizes do not match",
+            SlabError::PartialMerge { .. } => "slab array filled up during merge",
+        })
+    }
+}
+
+/// Fixed-length bit set indexed by slot.
+#[derive(Clone)]
+struct Bitmap {
+    words: Vec<u64>,
+}
+
+impl Bitmap {
+    fn new(bits: usize) -> Self {
+        Bitmap {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    fn clear(&mut self, index: usize) {
+        self.words[index / 64] &= !(1 << (index % 64));
+    }
+
+    fn resize(&mut self, bits: usize) {
+        self.words.resize(bits.div_ceil(64), 0);
+    }
+
+    /// Moves bits `at..len` into a new bitmap and truncates this one to `at`.
+    fn split_off(&mut self, at: usize, len: usize) -> Bitmap {
+        let mut tail = Bitmap::new(len - at);
+        for index in at..len {
+            if self.get(index) {
+                tail.set(index - at);
+            }
+        }
+        self.words.truncate(at.div_ceil(64));
+        if !at.is_multiple_of(64) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (at % 64)) - 1;
+            }
+        }
+        tail
+    }
+}
+
+/// Where the backing region of a slab came from, so `Drop` can return it.
+enum Backing {
+    Heap,
+    #[cfg(all(target_os = "linux", any(feature = "numa", feature = "huge_pages")))]
+    Mapped,
+    /// Mapping with an inaccessible page on either side of the region.
+    #[cfg(all(target_os = "linux", feature = "guard_pages"))]
+    Guarded,
+    /// Region split between several slabs, released by the last one dropped.
+    Shared(Arc<SharedRegion>),
+    /// Region owned by the caller of [`Slab::new_from_memory`]; never released.
+    Borrowed,
+}
+
+impl Backing {
+    /// Layout of a heap backing region of `size` bytes.
+    fn heap_layout(size: usize) -> Option<Layout> {
+        Layout::from_size_align(size, BACKING_ALIGN).ok()
+    }
+
+    /// # Safety
+    /// `memory` and `size` must describe the region this backing handed out.
+    unsafe fn release(&self, memory: NonNull<u8>, size: usize) {
+        match self {
+            Backing::Heap => {
+                dealloc(memory.as_ptr(), Backing::heap_layout(size).unwrap());
+            }
+            #[cfg(all(target_os = "linux", any(feature = "numa", feature = "huge_pages")))]
+            Backing::Mapped => sys::unmap(memory, size),
+            #[cfg(all(target_os = "linux", feature = "guard_pages"))]
+            Backing::Guarded => sys::unmap(memory.sub(PAGE_SIZE), size + 2 * PAGE_SIZE),
+            Backing::Shared(_) | Backing::Borrowed => {}
+        }
+    }
+}
+
+struct SharedRegion {
+    memory: NonNull<u8>,
+    size: usize,
+    backing: Backing,
+}
+
+// The region is only ever touched again to release it, by the last owner.
+unsafe impl Send for SharedRegion {}
+unsafe impl Sync for SharedRegion {}
+
+impl Drop for SharedRegion {
+    fn drop(&mut self) {
+        unsafe { self.backing.release(self.memory, self.size) }
+    }
+}
+
 pub struct Slab {
     memory: NonNull<u8>,
+    size: usize,
+    backing: Backing,
     free_list: Option<NonNull<FreeNode>>,
+    free_tail: Option<NonNull<FreeNode>>,
     object_size: usize,
+    /// Distance between slot starts: `object_size`, or more for
+    /// [`Slab::new_interleaved`] slabs.
+    stride: usize,
+    /// `log2(stride)` when the stride is a power of two, so slot lookups
+    /// shift instead of dividing.
+    stride_shift: Option<u32>,
+    requested_size: usize,
+    /// Padding before the first slot, staggering slots across cache lines.
+    color: usize,
     capacity: usize,
     allocated: usize,
+    hint: CacheHint,
+    init_order: InitOrder,
+    owner_id: u32,
+    numa_node: Option<u32>,
+    /// Slots that have been handed out at least once and may hold stale data.
+    #[cfg(feature = "fresh_zeroed")]
+    touched: Bitmap,
+    /// Slots currently handed out.
+    live: Bitmap,
+    /// Caller tag of each slot, meaningful while the slot is live.
+    #[cfg(feature = "tags")]
+    tags: Vec<u32>,
+    /// Time each slot was allocated or last touched, meaningful while the
+    /// slot is live; `None` for slots rebuilt from raw parts.
+    #[cfg(feature = "ttl")]
+    stamps: Vec<Option<Instant>>,
+    /// Set once the pages were handed back; the free list is rebuilt on the
+    /// next allocation.
+    #[cfg(feature = "std")]
+    decommitted: bool,
+    #[cfg(feature = "alloc_counter")]
+    total_allocs: AtomicU64,
+    #[cfg(feature = "alloc_counter")]
+    total_deallocs: AtomicU64,
+    /// [`Slab::checksum`] as of the last mutation made through the slab.
+    #[cfg(feature = "checksum")]
+    stored_checksum: u64,
 }
 
 impl Slab {
     pub fn new(object_size: usize) -> Option<Self> {
-        if object_size == 0 || object_size > MAX_OBJECT_SIZE {
+        Self::new_with_hint(object_size, CacheHint::Default)
+    }
+
+    /// [`Slab::new`] for a size known at compile time: sizes of 0 or past
+    /// `MAX_OBJECT_SIZE` fail to compile, and the slot stride is computed
+    /// at compile time. `None` only if the backing allocation fails.
+    pub fn new_const<const N: usize>() -> Option<Self> {
+        const { assert!(N > 0 && N <= MAX_OBJECT_SIZE, "object size must be in 1..=MAX_OBJECT_SIZE") };
+        Self::new_colored(N, const { Slab::align_size(N) }, CacheHint::Default, 0)
+    }
+
+    pub fn new_with_owner(object_size: usize, owner: u32) -> Option<Self> {
+        let mut slab = Self::new(object_size)?;
+        slab.owner_id = owner;
+        Some(slab)
+    }
+
+    pub fn new_with_hint(object_size: usize, hint: CacheHint) -> Option<Self> {
+        Self::new_colored(object_size, Self::align_size(object_size), hint, 0)
+    }
+
+    /// Lays the initial free list out in `order`. The order is kept when the
+    /// list is rebuilt after [`Slab::decommit`].
+    pub fn new_with_order(object_size: usize, order: InitOrder) -> Option<Self> {
+        let mut slab = Self::new(object_size)?;
+        slab.init_order = order;
+        slab.free_list = None;
+        slab.free_tail = None;
+        slab.init_free_list();
+        Some(slab)
+    }
+
+    /// Starts the first slot `color_offset` bytes into the backing region, so
+    /// objects of slabs with different offsets land on different cache lines.
+    /// The padding costs `color_offset / object_size` slots, rounded up.
+    /// Offsets that are not a multiple of a `usize` and of
+    /// [`Slab::slot_align`], or leave no room for a slot, are refused.
+    pub fn new_with_color(object_size: usize, color_offset: usize) -> Option<Self> {
+        Self::new_colored(object_size, Self::align_size(object_size), CacheHint::Default, color_offset)
+    }
+
+    /// Places slots `stride` bytes apart instead of packing them, so that
+    /// walking the same field of consecutive objects touches one cache line
+    /// each. `stride` must cover the rounded-up object size and be a
+    /// multiple of a `usize` and of [`Slab::slot_align`]; the slab holds
+    /// `SLAB_SIZE / stride` objects.
+    pub fn new_interleaved(object_size: usize, stride: usize) -> Option<Self> {
+        if stride < Self::align_size(object_size) || !stride.is_multiple_of(Self::placement_align()) {
             return None;
         }
+        Self::new_colored(object_size, stride, CacheHint::Default, 0)
+    }
 
-        let aligned_size = Self::align_size(object_size);
-        let capacity = SLAB_SIZE / aligned_size;
-        
-        if capacity == 0 {
+    fn new_colored(object_size: usize, stride: usize, hint: CacheHint, color: usize) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 || !color.is_multiple_of(Self::placement_align()) {
+            return None;
+        }
+        if SLAB_SIZE.saturating_sub(color) < stride {
             return None;
         }
 
         let memory = Self::allocate_memory(SLAB_SIZE)?;
-        let mut slab = Slab {
-            memory,
-            free_list: None,
-            object_size: aligned_size,
-            capacity,
-            allocated: 0,
+        let mut slab = Self::from_memory(memory, SLAB_SIZE, Backing::Heap, object_size);
+        slab.set_stride(stride);
+        slab.color = color;
+        slab.capacity = Self::slots_in(SLAB_SIZE - color, slab.stride);
+        #[cfg(feature = "fresh_zeroed")]
+        {
+            slab.touched = Bitmap::new(slab.capacity);
+        }
+        slab.live = Bitmap::new(slab.capacity);
+        #[cfg(feature = "tags")]
+        slab.tags.truncate(slab.capacity);
+        #[cfg(feature = "ttl")]
+        slab.stamps.truncate(slab.capacity);
+        slab.hint = hint;
+        slab.init_free_list();
+        Some(slab)
+    }
+
+    /// Granularity of slot offsets chosen by callers, so slots keep both the
+    /// slot alignment and the word alignment the free-list link needs.
+    const fn placement_align() -> usize {
+        let word = mem::align_of::<usize>();
+        if Self::slot_align() > word {
+            Self::slot_align()
+        } else {
+            word
+        }
+    }
+
+    /// Colour of the `index`-th slab of an allocator: successive slabs step
+    /// by a cache line through the bytes a slab leaves unused anyway, so
+    /// colouring never costs a slot.
+    fn color_for(object_size: usize, index: usize) -> usize {
+        let spare = SLAB_SIZE % Self::align_size(object_size);
+        let color = (index * CACHE_LINE_SIZE).checked_rem(spare).unwrap_or(0);
+        color - color % Self::placement_align()
+    }
+
+    /// Slab whose slots fit `layout`. Alignments above both a `usize` and
+    /// [`Slab::slot_align`], or above what the slot stride keeps, are refused.
+    pub fn from_layout(layout: Layout) -> Option<Self> {
+        if layout.align() > Self::placement_align() || !Self::align_size(layout.size()).is_multiple_of(layout.align()) {
+            return None;
+        }
+        Self::new(layout.size())
+    }
+
+    /// Sizes the backing region as the smallest power-of-two multiple of the
+    /// page size that holds at least `min_capacity` objects.
+    pub fn new_for_min_capacity(object_size: usize, min_capacity: usize) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 {
+            return None;
+        }
+
+        let needed = min_capacity.max(1).checked_mul(Self::align_size(object_size))?;
+        let size = needed.checked_next_power_of_two()?.max(PAGE_SIZE);
+        if size > MAX_SLAB_SIZE {
+            return None;
+        }
+
+        let memory = Self::allocate_memory(size)?;
+        let mut slab = Self::from_memory(memory, size, Backing::Heap, object_size);
+        slab.init_free_list();
+        Some(slab)
+    }
+
+    /// Places the slab on `numa_node` by mapping its backing region and binding
+    /// it with `mbind(MPOL_PREFERRED)`. Returns `None` if the kernel refuses
+    /// the mapping or the policy. Off Linux the regular allocator is used.
+    #[cfg(feature = "numa")]
+    pub fn new_with_numa_node(object_size: usize, numa_node: u32) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 {
+            return None;
+        }
+
+        #[cfg(target_os = "linux")]
+        let mut slab = {
+            let memory = sys::map_on_node(SLAB_SIZE, numa_node)?;
+            let mut slab = Self::from_memory(memory, SLAB_SIZE, Backing::Mapped, object_size);
+            slab.init_free_list();
+            slab
+        };
+        #[cfg(not(target_os = "linux"))]
+        let mut slab = Self::new(object_size)?;
+
+        slab.numa_node = Some(numa_node);
+        Some(slab)
+    }
+
+    /// Maps the backing region between two inaccessible pages, so a write
+    /// running off either end of the slab faults with `SIGSEGV` instead of
+    /// corrupting a neighbour. The guards cost address space, not memory.
+    #[cfg(all(target_os = "linux", feature = "guard_pages"))]
+    pub fn new_with_guard_pages(object_size: usize) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 {
+            return None;
+        }
+        let memory = sys::map_guarded(SLAB_SIZE, PAGE_SIZE)?;
+        let mut slab = Self::from_memory(memory, SLAB_SIZE, Backing::Guarded, object_size);
+        slab.init_free_list();
+        Some(slab)
+    }
+
+    /// Backs the slab with `n_pages` 2 MiB huge pages (`MAP_HUGETLB`) to cut
+    /// TLB misses on large object populations. Falls back to regular pages
+    /// when none are reserved, and to the regular allocator off Linux.
+    #[cfg(feature = "huge_pages")]
+    pub fn new_huge(object_size: usize, n_pages: usize) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 || n_pages == 0 {
+            return None;
+        }
+        let size = n_pages.checked_mul(HUGE_PAGE_SIZE)?;
+
+        #[cfg(target_os = "linux")]
+        let mut slab = {
+            let memory = sys::map(size, sys::MAP_HUGETLB).or_else(|| sys::map(size, 0))?;
+            Self::from_memory(memory, size, Backing::Mapped, object_size)
         };
+        #[cfg(not(target_os = "linux"))]
+        let mut slab = {
+            let memory = Self::allocate_memory(size)?;
+            Self::from_memory(memory, size, Backing::Heap, object_size)
+        };
+
+        slab.init_free_list();
+        Some(slab)
+    }
+
+    /// Builds a slab over caller-provided memory, which is left untouched on drop.
+    ///
+    /// # Safety
+    /// `memory` must be valid for reads and writes of `size` bytes, aligned to
+    /// a `usize`, and outlive the slab and every pointer it hands out.
+    pub unsafe fn new_from_memory(memory: NonNull<u8>, size: usize, object_size: usize) -> Option<Self> {
+        if Self::capacity_for(object_size) == 0 || !memory.as_ptr().cast::<usize>().is_aligned() {
+            return None;
+        }
+        if size < Self::align_size(object_size) {
+            return None;
+        }
 
+        let mut slab = Self::from_memory(memory, size, Backing::Borrowed, object_size);
+        // Nothing says the caller's memory is zeroed.
+        #[cfg(feature = "fresh_zeroed")]
+        for index in 0..slab.capacity {
+            slab.touched.set(index);
+        }
         slab.init_free_list();
         Some(slab)
     }
 
-    fn align_size(size: usize) -> usize {
-        let align = mem::align_of::<FreeNode>().max(8);
+    /// Takes the slab apart into its backing memory, region size, slot size,
+    /// requested object size, live count and free-list head, without
+    /// releasing the memory, so it
+    /// can be handed through FFI and rebuilt with [`Slab::from_raw_parts`].
+    /// Hint, owner, counters and other bookkeeping are dropped.
+    ///
+    /// # Panics
+    /// Panics unless the slab owns plain heap memory: mapped, shared,
+    /// borrowed, coloured and interleaved slabs cannot be described by
+    /// these parts.
+    pub fn into_raw_parts(mut self) -> (NonNull<u8>, usize, usize, usize, usize, Option<NonNull<FreeNode>>) {
+        assert!(
+            matches!(self.backing, Backing::Heap) && self.color == 0 && self.stride == self.object_size,
+            "only uncoloured heap slabs with packed slots can be taken apart"
+        );
+        self.recommit();
+        let parts = (self.memory, self.size, self.object_size, self.requested_size, self.allocated, self.free_list);
+        // The parts own the memory and live objects from here on.
+        self.backing = Backing::Borrowed;
+        self.allocated = 0;
+        parts
+    }
+
+    /// Rebuilds a slab from [`Slab::into_raw_parts`]. Every slot missing from
+    /// the free list is treated as live.
+    ///
+    /// # Safety
+    /// The parts must come from one `into_raw_parts` call, unchanged, and
+    /// be used once. The region and the free-list links stored in it must
+    /// not have been modified in between, except by writing to live objects.
+    pub unsafe fn from_raw_parts(
+        memory: NonNull<u8>,
+        size: usize,
+        object_size: usize,
+        requested_size: usize,
+        allocated: usize,
+        free_list: Option<NonNull<FreeNode>>,
+    ) -> Self {
+        let mut slab = Self::from_memory(memory, size, Backing::Heap, object_size);
+        slab.requested_size = requested_size;
+        for index in 0..slab.capacity {
+            #[cfg(feature = "fresh_zeroed")]
+            slab.touched.set(index);
+            slab.live.set(index);
+        }
+        slab.free_list = free_list;
+        let mut current = free_list;
+        while let Some(node) = current {
+            slab.live.clear(slab.slot_index(node.cast()));
+            slab.free_tail = current;
+            current = slab.next_of(node);
+        }
+        slab.allocated = allocated;
+        debug_assert_eq!(slab.allocated_set().count(), allocated, "free list does not match the live count");
+        slab.seal();
+        slab
+    }
+
+    /// Builds the slab metadata for a region; the caller sets up the free list.
+    fn from_memory(memory: NonNull<u8>, size: usize, backing: Backing, requested_size: usize) -> Self {
+        let object_size = Self::align_size(requested_size);
+        Slab {
+            memory,
+            size,
+            backing,
+            free_list: None,
+            free_tail: None,
+            object_size,
+            stride: object_size,
+            stride_shift: Self::shift_for(object_size),
+            requested_size,
+            color: 0,
+            capacity: Self::slots_in(size, object_size),
+            allocated: 0,
+            hint: CacheHint::Default,
+            init_order: InitOrder::Ascending,
+            owner_id: 0,
+            numa_node: None,
+            #[cfg(feature = "fresh_zeroed")]
+            touched: Bitmap::new(Self::slots_in(size, object_size)),
+            live: Bitmap::new(Self::slots_in(size, object_size)),
+            #[cfg(feature = "tags")]
+            tags: vec![0; Self::slots_in(size, object_size)],
+            #[cfg(feature = "ttl")]
+            stamps: vec![None; Self::slots_in(size, object_size)],
+            #[cfg(feature = "std")]
+            decommitted: false,
+            #[cfg(feature = "alloc_counter")]
+            total_allocs: AtomicU64::new(0),
+            #[cfg(feature = "alloc_counter")]
+            total_deallocs: AtomicU64::new(0),
+            #[cfg(feature = "checksum")]
+            stored_checksum: 0,
+        }
+    }
+
+    /// Smallest slot a slab can track: every free slot stores a [`FreeNode`]
+    /// link. Smaller requests are rounded up, or rejected with `strict_size`.
+    pub const fn min_object_size() -> usize {
+        mem::size_of::<FreeNode>()
+    }
+
+    /// Slots of `object_size` bytes that fit in `bytes`. Offset free lists
+    /// cannot index past [`NULL_OFFSET`], so the rest of a larger region
+    /// stays unused.
+    const fn slots_in(bytes: usize, object_size: usize) -> usize {
+        let slots = bytes / object_size;
+        #[cfg(feature = "offset_free_list")]
+        if slots > NULL_OFFSET as usize {
+            return NULL_OFFSET as usize;
+        }
+        slots
+    }
+
+    /// Alignment of every slot of a slab that owns its memory: `MIN_ALIGN`,
+    /// or only the alignment of the 2-byte link with `offset_free_list`.
+    /// Slots of a [`Slab::new_from_memory`] slab are only as aligned as the
+    /// memory handed in.
+    pub const fn slot_align() -> usize {
+        if cfg!(feature = "offset_free_list") || mem::align_of::<FreeNode>() > MIN_ALIGN {
+            mem::align_of::<FreeNode>()
+        } else {
+            MIN_ALIGN
+        }
+    }
+
+    const fn align_size(size: usize) -> usize {
+        let align = Self::slot_align();
         let node_size = mem::size_of::<FreeNode>();
-        size.max(node_size).next_multiple_of(align)
+        let size = if size > node_size { size } else { node_size };
+        size.next_multiple_of(align)
+    }
+
+    const fn capacity_for(object_size: usize) -> usize {
+        if object_size == 0 || object_size > MAX_OBJECT_SIZE {
+            return 0;
+        }
+        if cfg!(feature = "strict_size") && object_size < Self::min_object_size() {
+            return 0;
+        }
+        SLAB_SIZE / Self::align_size(object_size)
+    }
+
+    /// Objects a [`Slab::new`] slab of `object_size` holds, 0 for sizes no
+    /// slab serves. Usable in constants.
+    pub const fn capacity_for_object_size(object_size: usize) -> usize {
+        Self::capacity_for(object_size)
+    }
+
+    /// Bytes of a [`Slab::new`] slab of `object_size` that no slot covers,
+    /// 0 for sizes no slab serves.
+    pub const fn wasted_bytes_for_object_size(object_size: usize) -> usize {
+        match Self::capacity_for(object_size) {
+            0 => 0,
+            capacity => SLAB_SIZE - capacity * Self::align_size(object_size),
+        }
+    }
+
+    /// Whether slots for `requested` bytes tile a [`Slab::new`] slab with no
+    /// tail left over, e.g. `true` for 64 and `false` for 48 (4096 % 48 = 16).
+    /// `false` for sizes no slab serves.
+    pub const fn object_size_is_exact(requested: usize) -> bool {
+        Self::capacity_for(requested) != 0 && SLAB_SIZE.is_multiple_of(Self::align_size(requested))
     }
 
     /// # Safety
-    /// Allocates raw memory that must be deallocated with the same layout.
+    /// Allocates raw memory that must be deallocated with the same layout,
+    /// zeroed with `fresh_zeroed`.
     fn allocate_memory(size: usize) -> Option<NonNull<u8>> {
-        let layout = Layout::from_size_align(size, mem::align_of::<usize>()).ok()?;
-        unsafe {
-            let ptr = alloc(layout);
-            NonNull::new(ptr)
+        #[cfg(test)]
+        if tests::backing_alloc_fails() {
+            return None;
         }
+        let layout = Backing::heap_layout(size)?;
+        #[cfg(feature = "fresh_zeroed")]
+        let memory = NonNull::new(unsafe { alloc_zeroed(layout) })?;
+        #[cfg(not(feature = "fresh_zeroed"))]
+        let memory = NonNull::new(unsafe { alloc(layout) })?;
+        debug_assert!(
+            (memory.as_ptr() as usize).is_multiple_of(BACKING_ALIGN),
+            "allocator ignored the backing alignment"
+        );
+        Some(memory)
     }
 
     /// # Safety
     /// Initializes free list by writing to uninitialized memory within the slab.
     fn init_free_list(&mut self) {
-        let base = self.memory.as_ptr() as usize;
-        let mut prev: Option<NonNull<FreeNode>> = None;
+        match self.init_order {
+            InitOrder::Ascending => {
+                let mut prev: Option<NonNull<FreeNode>> = None;
 
-        for i in (0..self.capacity).rev() {
-            let offset = i * self.object_size;
-            let node_ptr = (base + offset) as *mut FreeNode;
-            
-            unsafe {
-                let node = &mut *node_ptr;
-                node.next = prev;
-                prev = NonNull::new(node_ptr);
+                for i in (0..self.capacity).rev() {
+                    let node_ptr = self.slot_node(i);
+                    self.set_next(node_ptr, prev);
+                    prev = Some(node_ptr);
+
+                    if self.free_tail.is_none() {
+                        self.free_tail = prev;
+                    }
+                }
+
+                self.free_list = prev;
+            }
+            InitOrder::Descending => {
+                for index in (0..self.capacity).rev() {
+                    self.link_back(self.slot_node(index));
+                }
+            }
+            InitOrder::Strided(stride) => {
+                let stride = stride.clamp(1, self.capacity.max(1));
+                for start in 0..stride {
+                    for index in (start..self.capacity).step_by(stride) {
+                        self.link_back(self.slot_node(index));
+                    }
+                }
             }
         }
-
-        self.free_list = prev;
+        self.seal();
     }
 
     /// # Safety
     /// Removes node from free list, assuming the pointer is valid and properly aligned.
     pub fn allocate(&mut self) -> Option<NonNull<u8>> {
+        self.recommit();
+        debug_assert!(
+            self.free_list.is_none_or(|node| self.contains(node.cast())),
+            "free list head points outside the slab"
+        );
         let node = self.free_list?;
-        
-        unsafe {
-            self.free_list = (*node.as_ptr()).next;
+        self.free_list = self.next_of(node);
+
+        if self.free_list.is_none() {
+            self.free_tail = None;
         }
-        
-        self.allocated += 1;
+
+        self.mark_allocated(self.slot_index(node.cast()));
         Some(node.cast())
     }
 
-    /// # Safety
-    /// Writes to the freed pointer, assuming it points to valid memory within this slab.
-    pub fn deallocate(&mut self, ptr: NonNull<u8>) {
-        let node_ptr = ptr.cast::<FreeNode>();
-        
-        unsafe {
-            (*node_ptr.as_ptr()).next = self.free_list;
+    /// Bookkeeping for a slot just taken off the free list.
+    fn mark_allocated(&mut self, index: usize) {
+        #[cfg(feature = "fresh_zeroed")]
+        self.touched.set(index);
+        self.live.set(index);
+        #[cfg(feature = "tags")]
+        {
+            self.tags[index] = 0;
         }
-        
-        self.free_list = Some(node_ptr);
-        self.allocated = self.allocated.saturating_sub(1);
+        #[cfg(feature = "ttl")]
+        {
+            self.stamps[index] = Some(Instant::now());
+        }
+        self.allocated += 1;
+        #[cfg(feature = "alloc_counter")]
+        self.total_allocs.fetch_add(1, Ordering::Relaxed);
+        self.seal();
     }
 
-    pub fn is_full(&self) -> bool {
-        self.allocated == self.capacity
+    /// Allocates the first free slot, in free-list order, whose address is a
+    /// multiple of `align`. Returns `None` if `align` is not a power of two or
+    /// no free slot is aligned.
+    pub fn try_allocate_aligned(&mut self, align: usize) -> Option<NonNull<u8>> {
+        if !align.is_power_of_two() {
+            return None;
+        }
+        self.recommit();
+        let mut current = self.free_list;
+        while let Some(node) = current {
+            if (node.as_ptr() as usize).is_multiple_of(align) {
+                self.unlink(node);
+                self.mark_allocated(self.slot_index(node.cast()));
+                return Some(node.cast());
+            }
+            current = self.next_of(node);
+        }
+        None
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.allocated == 0
-    }
+    /// Like `allocate`, but the returned object is always zeroed. Slots handed
+    /// out for the first time only need their free-list link cleared, since
+    /// the backing region starts zeroed; recycled slots are cleared in full.
+    #[cfg(feature = "fresh_zeroed")]
+    pub fn allocate_fresh_or_zeroed(&mut self) -> Option<NonNull<u8>> {
+        self.recommit();
+        let index = self.slot_index(self.free_list?.cast());
+        let len = if self.touched.get(index) {
+            self.object_size
+        } else {
+            mem::size_of::<FreeNode>()
+        };
 
-    pub fn contains(&self, ptr: NonNull<u8>) -> bool {
-        let addr = ptr.as_ptr() as usize;
-        let base = self.memory.as_ptr() as usize;
-        let end = base + SLAB_SIZE;
-        addr >= base && addr < end
+        let ptr = self.allocate()?;
+        unsafe { ptr.as_ptr().write_bytes(0, len) };
+        Some(ptr)
     }
-}
 
-impl Drop for Slab {
-    /// # Safety
-    /// Deallocates the slab memory using the same layout used during allocation.
-    fn drop(&mut self) {
-        let layout = Layout::from_size_align(SLAB_SIZE, mem::align_of::<usize>()).unwrap();
-        unsafe {
-            dealloc(self.memory.as_ptr(), layout);
-        }
+    /// Allocates a slot labelled with `tag`; plain allocations carry tag 0.
+    #[cfg(feature = "tags")]
+    pub fn allocate_tagged(&mut self, tag: u32) -> Option<NonNull<u8>> {
+        let ptr = self.allocate()?;
+        let index = self.slot_index(ptr);
+        self.tags[index] = tag;
+        Some(ptr)
     }
-}
 
-pub struct SlabAllocator {
-    slabs: [Option<Slab>; 16],
-    object_size: usize,
-}
+    /// Tag of the allocated slot at `ptr`, `None` for free or foreign pointers.
+    #[cfg(feature = "tags")]
+    pub fn tag_of(&self, ptr: NonNull<u8>) -> Option<u32> {
+        let index = self.slot_at(ptr)?;
+        self.live.get(index).then(|| self.tags[index])
+    }
 
-impl SlabAllocator {
-    pub const fn new(object_size: usize) -> Self {
-        const NONE: Option<Slab> = None;
-        SlabAllocator {
-            slabs: [NONE; 16],
-            object_size,
+    /// Restamps the live slot at `ptr` with `now`, postponing its expiry.
+    /// Returns `false` for free or foreign pointers.
+    #[cfg(feature = "ttl")]
+    pub fn touch(&mut self, ptr: NonNull<u8>, now: Instant) -> bool {
+        match self.slot_at(ptr).filter(|&index| self.live.get(index)) {
+            Some(index) => {
+                self.stamps[index] = Some(now);
+                true
+            }
+            None => false,
         }
     }
 
-    pub fn allocate(&mut self) -> Option<NonNull<u8>> {
-        for slab in self.slabs.iter_mut().flatten() {
-            if !slab.is_full() {
-                if let Some(ptr) = slab.allocate() {
-                    return Some(ptr);
-                }
+    /// Frees every live slot stamped more than `older_than` before `now`,
+    /// handing each to `f` first while its contents are still intact. Slots
+    /// are stamped when allocated and by [`Slab::touch`]; slots rebuilt by
+    /// [`Slab::from_raw_parts`] carry no stamp and never expire.
+    #[cfg(feature = "ttl")]
+    pub fn expire(&mut self, older_than: Duration, now: Instant, mut f: impl FnMut(NonNull<u8>)) {
+        for index in 0..self.capacity {
+            let stale = self.stamps[index].is_some_and(|stamp| now.saturating_duration_since(stamp) > older_than);
+            if self.live.get(index) && stale {
+                let ptr = self.slot_node(index).cast();
+                f(ptr);
+                self.deallocate(ptr);
             }
         }
+    }
+
+    /// Allocates one slot in `hint` order, leaving the slab's own hint as it
+    /// was. The free list is ordered by that hint when slots are freed, so a
+    /// matching hint pops the head; an opposite one takes the tail, which costs
+    /// a walk of the list.
+    pub fn allocate_with_hint(&mut self, hint: CacheHint) -> Option<NonNull<u8>> {
+        if (hint == CacheHint::Fifo) == (self.hint == CacheHint::Fifo) {
+            return self.allocate();
+        }
+        self.recommit();
+        let node = self.free_tail?;
+        self.unlink(node);
+        self.mark_allocated(self.slot_index(node.cast()));
+        Some(node.cast())
+    }
 
-        for slot in self.slabs.iter_mut() {
-            if slot.is_none() {
-                *slot = Slab::new(self.object_size);
-                if let Some(slab) = slot {
-                    return slab.allocate();
+    /// Removes `node` from the free list, walking it to find the predecessor.
+    /// Returns `false` if `node` is not on the list.
+    fn unlink(&mut self, node: NonNull<FreeNode>) -> bool {
+        self.recommit();
+        let mut prev = None;
+        let mut current = self.free_list;
+        while let Some(candidate) = current {
+            let next = self.next_of(candidate);
+            if candidate == node {
+                match prev {
+                    Some(prev) => self.set_next(prev, next),
+                    None => self.free_list = next,
                 }
+                if self.free_tail == Some(node) {
+                    self.free_tail = prev;
+                }
+                return true;
             }
+            prev = current;
+            current = next;
         }
-
-        None
+        false
     }
 
-    pub fn deallocate(&mut self, ptr: NonNull<u8>) {
-        for slab in self.slabs.iter_mut().flatten() {
-            if slab.contains(ptr) {
+    /// # Safety
+    /// Writes to the freed pointer, assuming it points to valid memory within this slab.
+    pub fn deallocate(&mut self, ptr: NonNull<u8>) {
+        let node_ptr = ptr.cast::<FreeNode>();
+        self.live.clear(self.slot_index(ptr));
+
+        match self.hint {
+            CacheHint::Fifo => self.link_back(node_ptr),
+            CacheHint::Lifo | CacheHint::Default => {
+                self.set_next(node_ptr, self.free_list);
+                if self.free_list.is_none() {
+                    self.free_tail = Some(node_ptr);
+                }
+                self.free_list = Some(node_ptr);
+            }
+        }
+
+        self.allocated = self.allocated.saturating_sub(1);
+        #[cfg(feature = "alloc_counter")]
+        self.total_deallocs.fetch_add(1, Ordering::Relaxed);
+        self.seal();
+    }
+
+    /// [`Slab::deallocate`] for objects holding secrets: the whole slot is
+    /// zeroed with volatile writes the compiler cannot drop, then the
+    /// free-list link is written over its first bytes as usual.
+    ///
+    /// # Safety
+    /// Same contract as [`Slab::deallocate`]; the slot is overwritten in full.
+    pub fn deallocate_secure(&mut self, ptr: NonNull<u8>) {
+        for offset in 0..self.object_size {
+            unsafe { ptr.as_ptr().add(offset).write_volatile(0) };
+        }
+        self.deallocate(ptr);
+    }
+
+    /// Lifetime number of successful allocations; never decremented.
+    #[cfg(feature = "alloc_counter")]
+    pub fn total_allocs(&self) -> u64 {
+        self.total_allocs.load(Ordering::Relaxed)
+    }
+
+    /// Lifetime number of deallocations; never decremented.
+    #[cfg(feature = "alloc_counter")]
+    pub fn total_deallocs(&self) -> u64 {
+        self.total_deallocs.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "alloc_counter")]
+    pub fn reset_counters(&self) {
+        self.total_allocs.store(0, Ordering::Relaxed);
+        self.total_deallocs.store(0, Ordering::Relaxed);
+    }
+
+    pub fn hint(&self) -> CacheHint {
+        self.hint
+    }
+
+    pub fn owner(&self) -> u32 {
+        self.owner_id
+    }
+
+    pub fn numa_node(&self) -> Option<u32> {
+        self.numa_node
+    }
+
+    /// Distance in bytes between the starts of neighbouring slots.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn set_hint(&mut self, hint: CacheHint) {
+        self.hint = hint;
+    }
+
+    /// Extends the backing region by `additional_pages` pages and queues the
+    /// new slots at the head of the free list. Returns `false` on OOM or when
+    /// the slab would exceed the maximum backing size.
+    ///
+    /// If `realloc` has to move the region, every pointer previously handed out
+    /// by this slab is invalidated; the free list itself is rebased.
+    pub fn grow(&mut self, additional_pages: usize) -> bool {
+        if !matches!(self.backing, Backing::Heap) {
+            return false;
+        }
+        let Some(new_size) = additional_pages
+            .checked_mul(PAGE_SIZE)
+            .and_then(|extra| extra.checked_add(self.size))
+        else {
+            return false;
+        };
+        if new_size > MAX_SLAB_SIZE {
+            return false;
+        }
+
+        let old_base = self.memory.as_ptr() as usize;
+        let layout = Backing::heap_layout(self.size).unwrap();
+        let Some(memory) = NonNull::new(unsafe { realloc(self.memory.as_ptr(), layout, new_size) }) else {
+            return false;
+        };
+        #[cfg(feature = "fresh_zeroed")]
+        unsafe { memory.as_ptr().add(self.size).write_bytes(0, new_size - self.size) };
+        self.memory = memory;
+        self.size = new_size;
+        if memory.as_ptr() as usize != old_base {
+            self.rebase_free_list(old_base);
+        }
+
+        let old_capacity = self.capacity;
+        self.capacity = Self::slots_in(new_size - self.color, self.stride);
+        #[cfg(feature = "fresh_zeroed")]
+        self.touched.resize(self.capacity);
+        self.live.resize(self.capacity);
+        #[cfg(feature = "tags")]
+        self.tags.resize(self.capacity, 0);
+        #[cfg(feature = "ttl")]
+        self.stamps.resize(self.capacity, None);
+        let mut head = self.free_list;
+        for i in (old_capacity..self.capacity).rev() {
+            let node = self.slot_node(i);
+            self.set_next(node, head);
+            if head.is_none() {
+                self.free_tail = Some(node);
+            }
+            head = Some(node);
+        }
+        self.free_list = head;
+        self.seal();
+        true
+    }
+
+    /// Gives back the backing memory past `target_capacity` slots when every
+    /// live object sits below that index, then relinks the remaining free
+    /// slots in address order. Returns `false` when the slab is not heap
+    /// backed, a live object is in the way, or `realloc` fails.
+    ///
+    /// Like [`Slab::grow`], a `realloc` that moves the region invalidates the
+    /// pointers handed out by this slab.
+    pub fn try_shrink(&mut self, target_capacity: usize) -> bool {
+        if !matches!(self.backing, Backing::Heap) || target_capacity == 0 || target_capacity >= self.capacity {
+            return false;
+        }
+        if (target_capacity..self.capacity).any(|index| self.live.get(index)) {
+            return false;
+        }
+        self.recommit();
+
+        let new_size = self.color + target_capacity * self.stride;
+        let layout = Backing::heap_layout(self.size).unwrap();
+        let Some(memory) = NonNull::new(unsafe { realloc(self.memory.as_ptr(), layout, new_size) }) else {
+            return false;
+        };
+        self.memory = memory;
+        self.size = new_size;
+        self.capacity = target_capacity;
+        #[cfg(feature = "fresh_zeroed")]
+        self.touched.resize(target_capacity);
+        self.live.resize(target_capacity);
+        #[cfg(feature = "tags")]
+        self.tags.truncate(target_capacity);
+        #[cfg(feature = "ttl")]
+        self.stamps.truncate(target_capacity);
+
+        self.free_list = None;
+        self.free_tail = None;
+        for index in 0..target_capacity {
+            if !self.live.get(index) {
+                self.link_back(self.slot_node(index));
+            }
+        }
+        self.seal();
+        true
+    }
+
+    /// Splits off slots `split_capacity..capacity` into a new slab over the
+    /// same backing region, which is released once both halves are dropped.
+    /// Live objects stay valid and belong to whichever half covers them.
+    pub fn split_at(&mut self, split_capacity: usize) -> Option<Slab> {
+        if split_capacity == 0 || split_capacity >= self.capacity {
+            return None;
+        }
+        self.recommit();
+
+        let region = self.share_backing();
+        let offset = self.color + split_capacity * self.stride;
+        let memory = unsafe { NonNull::new_unchecked(self.memory.as_ptr().add(offset)) };
+        let mut upper = Slab::from_memory(memory, self.size - offset, Backing::Shared(region), self.requested_size);
+        upper.set_stride(self.stride);
+        upper.capacity = self.capacity - split_capacity;
+        upper.hint = self.hint;
+        upper.init_order = self.init_order;
+        upper.owner_id = self.owner_id;
+        upper.numa_node = self.numa_node;
+        #[cfg(feature = "fresh_zeroed")]
+        {
+            upper.touched = self.touched.split_off(split_capacity, self.capacity);
+        }
+        upper.live = self.live.split_off(split_capacity, self.capacity);
+        #[cfg(feature = "tags")]
+        {
+            upper.tags = self.tags.split_off(split_capacity);
+        }
+        #[cfg(feature = "ttl")]
+        {
+            upper.stamps = self.stamps.split_off(split_capacity);
+        }
+
+        let mut current = self.free_list.take();
+        self.free_tail = None;
+        self.size = offset;
+        self.capacity = split_capacity;
+        let mut upper_free = 0;
+        while let Some(node) = current {
+            current = self.next_of(node);
+            if upper.contains(node.cast()) {
+                upper.link_back(node);
+                upper_free += 1;
+            } else {
+                self.link_back(node);
+            }
+        }
+
+        upper.allocated = upper.capacity - upper_free;
+        self.allocated -= upper.allocated;
+        self.seal();
+        upper.seal();
+        Some(upper)
+    }
+
+    fn share_backing(&mut self) -> Arc<SharedRegion> {
+        if let Backing::Shared(region) = &self.backing {
+            return region.clone();
+        }
+        let backing = mem::replace(&mut self.backing, Backing::Heap);
+        let region = Arc::new(SharedRegion {
+            memory: self.memory,
+            size: self.size,
+            backing,
+        });
+        self.backing = Backing::Shared(region.clone());
+        region
+    }
+
+    /// # Safety
+    /// Appends a node that lies within this slab to the tail of the free list.
+    fn link_back(&mut self, node: NonNull<FreeNode>) {
+        self.set_next(node, None);
+        match self.free_tail {
+            Some(tail) => self.set_next(tail, Some(node)),
+            None => self.free_list = Some(node),
+        }
+        self.free_tail = Some(node);
+    }
+
+    /// # Safety
+    /// Reads the link stored in `node`, which must be a free slot of this slab.
+    fn next_of(&self, node: NonNull<FreeNode>) -> Option<NonNull<FreeNode>> {
+        let next = unsafe { (*node.as_ptr()).next };
+        #[cfg(feature = "offset_free_list")]
+        let next = (next != NULL_OFFSET).then(|| self.slot_node(next as usize));
+        next
+    }
+
+    /// # Safety
+    /// Stores `next` in `node`; both must be slots of this slab.
+    fn set_next(&self, node: NonNull<FreeNode>, next: Option<NonNull<FreeNode>>) {
+        #[cfg(feature = "offset_free_list")]
+        let next = next.map_or(NULL_OFFSET, |next| self.slot_index(next.cast()) as u16);
+        unsafe { (*node.as_ptr()).next = next };
+    }
+
+    const fn shift_for(stride: usize) -> Option<u32> {
+        if stride.is_power_of_two() {
+            Some(stride.trailing_zeros())
+        } else {
+            None
+        }
+    }
+
+    fn set_stride(&mut self, stride: usize) {
+        self.stride = stride;
+        self.stride_shift = Self::shift_for(stride);
+    }
+
+    /// Index of the slot at `offset` past the colour padding.
+    fn index_at(&self, offset: usize) -> usize {
+        match self.stride_shift {
+            Some(shift) => offset >> shift,
+            None => offset / self.stride,
+        }
+    }
+
+    /// Whether `offset` past the colour padding is the start of a slot.
+    fn is_slot_offset(&self, offset: usize) -> bool {
+        match self.stride_shift {
+            Some(shift) => offset & ((1 << shift) - 1) == 0,
+            None => offset.is_multiple_of(self.stride),
+        }
+    }
+
+    fn slot_index(&self, ptr: NonNull<u8>) -> usize {
+        self.index_at(ptr.as_ptr() as usize - self.memory.as_ptr() as usize - self.color)
+    }
+
+    /// # Safety
+    /// Computes the address of slot `index`, which must be below `capacity`.
+    fn slot_node(&self, index: usize) -> NonNull<FreeNode> {
+        let ptr = unsafe { self.memory.as_ptr().add(self.color + index * self.stride) };
+        unsafe { NonNull::new_unchecked(ptr.cast()) }
+    }
+
+    /// # Safety
+    /// Rewrites every free-list link that still points into the region that
+    /// used to start at `old_base` so it points at the same offset in `memory`.
+    /// Offset links are relative already, so only the head and tail move.
+    fn rebase_free_list(&mut self, old_base: usize) {
+        let memory = self.memory;
+        let translate = move |node: NonNull<FreeNode>| {
+            let offset = node.as_ptr() as usize - old_base;
+            unsafe { NonNull::new_unchecked(memory.as_ptr().add(offset).cast()) }
+        };
+
+        self.free_list = self.free_list.map(translate);
+        self.free_tail = self.free_tail.map(translate);
+        #[cfg(not(feature = "offset_free_list"))]
+        let mut current = self.free_list;
+        #[cfg(not(feature = "offset_free_list"))]
+        while let Some(node) = current {
+            unsafe {
+                let next = (*node.as_ptr()).next.map(translate);
+                (*node.as_ptr()).next = next;
+                current = next;
+            }
+        }
+    }
+
+    /// Hands the pages of an empty slab back to the OS with
+    /// `madvise(MADV_DONTNEED)` while keeping the addresses valid; they fault
+    /// back in as zeroes on the next allocation. Only whole pages inside the
+    /// region are released. Returns whether anything was released, which is
+    /// never the case for a non-empty slab or without the `std` feature on Linux.
+    pub fn decommit(&mut self) -> bool {
+        #[cfg(all(target_os = "linux", feature = "std"))]
+        if self.is_empty() {
+            let start = (self.memory.as_ptr() as usize).next_multiple_of(PAGE_SIZE);
+            let end = (self.memory.as_ptr() as usize + self.size) / PAGE_SIZE * PAGE_SIZE;
+            if start >= end {
+                return false;
+            }
+
+            let offset = start - self.memory.as_ptr() as usize;
+            let pages = unsafe { NonNull::new_unchecked(self.memory.as_ptr().add(offset)) };
+            if unsafe { sys::discard(pages, end - start) } {
+                // Discarded pages read back as zeroes, so slots lying wholly
+                // inside them count as fresh again.
+                #[cfg(feature = "fresh_zeroed")]
+                for index in 0..self.capacity {
+                    let slot = self.slot_node(index).as_ptr() as usize;
+                    if slot >= start && slot + self.object_size <= end {
+                        self.touched.clear(index);
+                    }
+                }
+                self.free_list = None;
+                self.free_tail = None;
+                self.decommitted = true;
+                self.seal();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Scrubs the payload of every free slot, keeping the free-list link at
+    /// the start of each slot intact. Meant to be called explicitly after
+    /// freeing sensitive data, not on every deallocation.
+    pub fn write_zeros_free_slots(&mut self) {
+        let header = mem::size_of::<FreeNode>();
+        let mut current = self.free_list;
+        while let Some(node) = current {
+            current = self.next_of(node);
+            unsafe { node.cast::<u8>().as_ptr().add(header).write_bytes(0, self.object_size - header) };
+        }
+    }
+
+    /// Folds the slab metadata into a 64-bit FNV-1a hash: slot size,
+    /// capacity, live count and both ends of the free list. A mismatch with
+    /// the value stored on the last mutation means the metadata was written
+    /// from outside the slab.
+    #[cfg(feature = "checksum")]
+    pub fn checksum(&self) -> u64 {
+        let head = self.free_list.map_or(0, |node| node.as_ptr() as usize);
+        let tail = self.free_tail.map_or(0, |node| node.as_ptr() as usize);
+        [self.object_size, self.capacity, self.allocated, head, tail]
+            .into_iter()
+            .flat_map(|word| (word as u64).to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3))
+    }
+
+    #[cfg(feature = "checksum")]
+    pub fn verify_checksum(&self) -> bool {
+        self.checksum() == self.stored_checksum
+    }
+
+    /// Records the checksum after a mutation; a no-op without `checksum`.
+    fn seal(&mut self) {
+        #[cfg(feature = "checksum")]
+        {
+            self.stored_checksum = self.checksum();
+        }
+    }
+
+    /// Frees every slot at once and rebuilds the free list in the slab's
+    /// initial order, keeping the backing memory. No destructor runs on the
+    /// objects that were live.
+    pub fn reset(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.decommitted = false;
+        }
+        self.live = Bitmap::new(self.capacity);
+        self.allocated = 0;
+        self.free_list = None;
+        self.free_tail = None;
+        self.init_free_list();
+    }
+
+    fn recommit(&mut self) {
+        #[cfg(feature = "std")]
+        if mem::take(&mut self.decommitted) {
+            self.free_list = None;
+            self.free_tail = None;
+            self.init_free_list();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.allocated == self.capacity
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allocated == 0
+    }
+
+    /// Compares fill ratios by cross-multiplying to stay in integers.
+    fn fuller_than(&self, other: &Slab) -> bool {
+        self.allocated * other.capacity > other.allocated * self.capacity
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.capacity - self.allocated
+    }
+
+    /// Nodes on the free list, counted by walking it. The walk gives up one
+    /// node past `capacity`, so a cyclic list reads as too deep instead of
+    /// hanging.
+    pub fn free_list_depth(&self) -> usize {
+        let mut depth = 0;
+        let mut current = self.free_list;
+        while let Some(node) = current {
+            if depth > self.capacity {
+                break;
+            }
+            depth += 1;
+            current = self.next_of(node);
+        }
+        depth
+    }
+
+    /// Whether the free list holds exactly `capacity - allocated` nodes,
+    /// i.e. no path updated the live count without the list or the other way
+    /// round. A decommitted slab has no list until its next allocation.
+    pub fn is_consistent(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.decommitted {
+            return self.free_list.is_none();
+        }
+        self.capacity.checked_sub(self.allocated) == Some(self.free_list_depth())
+    }
+
+    /// Writes a header line followed by a hex + ASCII dump of every slot in
+    /// 16-byte rows, each slot tagged `[alloc]` or `[free]`.
+    #[cfg(feature = "debug")]
+    pub fn debug_hexdump<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        writeln!(
+            w,
+            "slab {:p} object_size={} capacity={} allocated={}",
+            self.memory, self.object_size, self.capacity, self.allocated
+        )?;
+
+        for index in 0..self.capacity {
+            let tag = if self.live.get(index) { "[alloc]" } else { "[free]" };
+            writeln!(w, "slot {index} {tag}")?;
+
+            let base = self.color + index * self.stride;
+            let bytes = unsafe { slice::from_raw_parts(self.memory.as_ptr().add(base), self.object_size) };
+            for (row, chunk) in bytes.chunks(16).enumerate() {
+                write!(w, "  {:06x} ", base + row * 16)?;
+                for byte in chunk {
+                    write!(w, " {byte:02x}")?;
+                }
+                for _ in chunk.len()..16 {
+                    w.write_str("   ")?;
+                }
+                w.write_str("  |")?;
+                for &byte in chunk {
+                    let shown = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                    w.write_char(shown)?;
+                }
+                w.write_str("|\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// [`SizeClass`] of the slot size; `None` for slots past `MAX_OBJECT_SIZE`.
+    pub fn object_size_class(&self) -> Option<SizeClass> {
+        SizeClass::for_size(self.object_size)
+    }
+
+    /// Fill level scaled to `0..=255`: 0 when empty, 255 when full.
+    pub fn memory_pressure_score(&self) -> u8 {
+        if self.capacity == 0 {
+            return u8::MAX;
+        }
+        (self.allocated * 255 / self.capacity) as u8
+    }
+
+    pub fn total_allocation_bytes(&self) -> usize {
+        self.allocated * self.object_size
+    }
+
+    pub fn total_capacity_bytes(&self) -> usize {
+        self.capacity * self.object_size
+    }
+
+    pub fn free_bytes(&self) -> usize {
+        self.free_count() * self.object_size
+    }
+
+    /// Colour padding plus the tail of the backing region too small to hold
+    /// one more slot.
+    pub fn wasted_bytes(&self) -> usize {
+        self.size - self.total_capacity_bytes()
+    }
+
+    pub fn stats(&self) -> SlabStats {
+        SlabStats {
+            object_size: self.object_size,
+            requested_size: self.requested_size,
+            capacity: self.capacity,
+            allocated: self.allocated,
+            free_count: self.free_count(),
+            bytes_allocated: self.total_allocation_bytes(),
+            bytes_capacity: self.total_capacity_bytes(),
+        }
+    }
+
+    pub fn contains(&self, ptr: NonNull<u8>) -> bool {
+        let addr = ptr.as_ptr() as usize;
+        let base = self.memory.as_ptr() as usize;
+        let end = base + self.size;
+        addr >= base && addr < end
+    }
+
+    /// [`Slab::contains`] for a batch: the bounds are read once and the scan
+    /// stops at the first pointer outside them.
+    pub fn contains_all(&self, ptrs: &[NonNull<u8>]) -> bool {
+        let base = self.memory.as_ptr() as usize;
+        let end = base + self.size;
+        ptrs.iter().all(|ptr| (base..end).contains(&(ptr.as_ptr() as usize)))
+    }
+
+    /// [`Slab::is_valid_ptr`] for a batch: every pointer must also start a
+    /// slot, not just fall inside the slab.
+    pub fn all_valid_ptrs(&self, ptrs: &[NonNull<u8>]) -> bool {
+        self.contains_all(ptrs) && ptrs.iter().all(|&ptr| self.slot_at(ptr).is_some())
+    }
+
+    /// How many of `ptrs` start a slot of this slab, with
+    /// [`Slab::is_valid_ptr`] semantics.
+    pub fn contains_exact_count(&self, ptrs: &[NonNull<u8>]) -> usize {
+        ptrs.iter().filter(|&&ptr| self.is_valid_ptr(ptr)).count()
+    }
+
+    /// Address of slot `index`, or `None` past the end of the slab.
+    pub fn object_ptr(&self, index: usize) -> Option<NonNull<u8>> {
+        (index < self.capacity).then(|| self.slot_node(index).cast())
+    }
+
+    /// Indices of the allocated slots in ascending order, read from the live
+    /// bitmap in O(capacity).
+    pub fn allocated_set(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.capacity).filter(|&index| self.live.get(index))
+    }
+
+    /// Length of the longest run of adjacent free slots, scanned from the
+    /// live bitmap in O(capacity). Meant for planning a batch of neighbouring
+    /// allocations, not for the allocation hot path.
+    pub fn max_contiguous_free(&self) -> usize {
+        let (mut longest, mut run) = (0, 0);
+        for index in 0..self.capacity {
+            if self.live.get(index) {
+                run = 0;
+            } else {
+                run += 1;
+                longest = longest.max(run);
+            }
+        }
+        longest
+    }
+
+    /// [`Slab::allocated_set`] mapped to slot addresses.
+    pub fn allocated_addresses(&self) -> impl Iterator<Item = NonNull<u8>> + '_ {
+        self.allocated_set().map(|index| self.slot_node(index).cast())
+    }
+
+    /// Same slots as [`Slab::allocated_addresses`], but holds the slab
+    /// mutably so no slot can be allocated or freed while the caller writes
+    /// through the yielded pointers.
+    pub fn iter_allocated_mut(&mut self) -> impl Iterator<Item = NonNull<u8>> + '_ {
+        let slab: &Slab = self;
+        slab.allocated_addresses()
+    }
+
+    /// Index of the slot starting at `ptr`, if any.
+    fn slot_at(&self, ptr: NonNull<u8>) -> Option<usize> {
+        let index = self.slot_containing(ptr)?;
+        (self.slot_node(index).cast() == ptr).then_some(index)
+    }
+
+    /// Index of the slot whose bytes include `ptr`; `None` in the colour
+    /// padding, the unused tail, or outside the slab.
+    fn slot_containing(&self, ptr: NonNull<u8>) -> Option<usize> {
+        if !self.contains(ptr) {
+            return None;
+        }
+        let offset = (ptr.as_ptr() as usize - self.memory.as_ptr() as usize).checked_sub(self.color)?;
+        let index = self.index_at(offset);
+        (index < self.capacity).then_some(index)
+    }
+
+    /// Whether `ptr` is the start of a slot of this slab, allocated or not.
+    pub fn is_valid_ptr(&self, ptr: NonNull<u8>) -> bool {
+        self.slot_at(ptr).is_some()
+    }
+
+    /// Frees every allocated slot for which `f` returns `false`, visiting the
+    /// slots in address order. The slab is borrowed for the whole sweep, so
+    /// `f` cannot allocate or free behind its back.
+    pub fn retain(&mut self, mut f: impl FnMut(NonNull<u8>) -> bool) {
+        for index in 0..self.capacity {
+            if self.live.get(index) {
+                let ptr = self.slot_node(index).cast();
+                if !f(ptr) {
+                    self.deallocate(ptr);
+                }
+            }
+        }
+    }
+
+    /// Whether `ptr` is the start of a slot that is currently allocated.
+    /// Pointers into the middle of a slot or outside the slab are never live.
+    pub fn is_allocated(&self, ptr: NonNull<u8>) -> bool {
+        self.slot_at(ptr).is_some_and(|index| self.live.get(index))
+    }
+
+    /// Copies a whole slot from `src` to `dst`. Both must be slots of this
+    /// slab; whether they are allocated is up to the caller, so copying onto
+    /// a free slot clobbers its free-list link. Returns `false` for invalid
+    /// pointers.
+    pub fn copy_object(&mut self, src: NonNull<u8>, dst: NonNull<u8>) -> bool {
+        if !self.is_valid_ptr(src) || !self.is_valid_ptr(dst) {
+            return false;
+        }
+        if src != dst {
+            unsafe { ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), self.object_size) };
+        }
+        true
+    }
+
+    /// [`Slab::copy_object`] that also requires `src` to be allocated and
+    /// `dst` to be free. Since the copy overwrites the free-list link, `dst`
+    /// is taken off the free list and counted as allocated; freeing `src`
+    /// afterwards completes a move.
+    pub fn copy_object_checked(&mut self, src: NonNull<u8>, dst: NonNull<u8>) -> bool {
+        if !self.is_allocated(src) || !self.is_valid_ptr(dst) || self.is_allocated(dst) {
+            return false;
+        }
+        if !self.unlink(dst.cast()) {
+            return false;
+        }
+        let index = self.slot_index(dst);
+        self.mark_allocated(index);
+        #[cfg(feature = "tags")]
+        {
+            self.tags[index] = self.tags[self.slot_index(src)];
+        }
+        #[cfg(feature = "ttl")]
+        {
+            self.stamps[index] = self.stamps[self.slot_index(src)];
+        }
+        unsafe { ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), self.object_size) };
+        true
+    }
+
+    /// Exchanges backing memory and free-list state with `other`. Live objects
+    /// stay where they are and are afterwards owned by the other slab.
+    ///
+    /// # Panics
+    /// Panics if the two slabs do not share the same object size.
+    pub fn swap(&mut self, other: &mut Slab) {
+        assert_eq!(
+            self.object_size, other.object_size,
+            "cannot swap slabs with different object sizes"
+        );
+        mem::swap(self, other);
+    }
+
+    pub fn overlaps(&self, other: &Slab) -> bool {
+        let base = self.memory.as_ptr() as usize;
+        let other_base = other.memory.as_ptr() as usize;
+        base < other_base + other.size && other_base < base + self.size
+    }
+
+    /// Walks the free list and checks it against the slab bookkeeping: every
+    /// node is a free slot of this slab, the list is acyclic, ends at
+    /// `free_tail`, and its length matches the free and live counts.
+    pub fn validate(&self) -> bool {
+        #[cfg(feature = "std")]
+        let expected_free = if self.decommitted { 0 } else { self.free_count() };
+        #[cfg(not(feature = "std"))]
+        let expected_free = self.free_count();
+
+        let mut walked = 0;
+        let mut last = None;
+        let mut current = self.free_list;
+        while let Some(node) = current {
+            if walked == expected_free || !self.contains(node.cast()) {
+                return false;
+            }
+            let Some(offset) = (node.as_ptr() as usize - self.memory.as_ptr() as usize).checked_sub(self.color) else {
+                return false;
+            };
+            let index = self.index_at(offset);
+            if !self.is_slot_offset(offset) || index >= self.capacity || self.live.get(index) {
+                return false;
+            }
+            walked += 1;
+            last = current;
+            current = self.next_of(node);
+        }
+
+        #[cfg(feature = "checksum")]
+        if !self.verify_checksum() {
+            return false;
+        }
+
+        walked == expected_free
+            && last == self.free_tail
+            && self.allocated_set().count() == self.allocated
+    }
+}
+
+impl Clone for Slab {
+    /// Copies the whole backing region into fresh heap memory and rebases the
+    /// free list onto it, so live objects are duplicated at the same offsets.
+    /// A slab placed on a NUMA node is copied onto the same node when the
+    /// kernel allows it.
+    fn clone(&self) -> Self {
+        #[cfg(all(target_os = "linux", feature = "numa"))]
+        let mapped = self.numa_node.and_then(|node| sys::map_on_node(self.size, node));
+        #[cfg(not(all(target_os = "linux", feature = "numa")))]
+        let mapped: Option<NonNull<u8>> = None;
+        let (memory, backing) = match mapped {
+            #[cfg(all(target_os = "linux", feature = "numa"))]
+            Some(memory) => (memory, Backing::Mapped),
+            _ => match Self::allocate_memory(self.size) {
+                Some(memory) => (memory, Backing::Heap),
+                None => handle_alloc_error(Backing::heap_layout(self.size).unwrap()),
+            },
+        };
+        unsafe { ptr::copy_nonoverlapping(self.memory.as_ptr(), memory.as_ptr(), self.size) };
+
+        let mut slab = Slab::from_memory(memory, self.size, backing, self.requested_size);
+        slab.free_list = self.free_list;
+        slab.free_tail = self.free_tail;
+        slab.rebase_free_list(self.memory.as_ptr() as usize);
+        slab.set_stride(self.stride);
+        slab.color = self.color;
+        slab.capacity = self.capacity;
+        slab.allocated = self.allocated;
+        slab.hint = self.hint;
+        slab.init_order = self.init_order;
+        slab.owner_id = self.owner_id;
+        slab.numa_node = self.numa_node;
+        #[cfg(feature = "fresh_zeroed")]
+        {
+            slab.touched = self.touched.clone();
+        }
+        slab.live = self.live.clone();
+        #[cfg(feature = "tags")]
+        {
+            slab.tags = self.tags.clone();
+        }
+        #[cfg(feature = "ttl")]
+        {
+            slab.stamps = self.stamps.clone();
+        }
+        #[cfg(feature = "std")]
+        {
+            slab.decommitted = self.decommitted;
+        }
+        #[cfg(feature = "alloc_counter")]
+        {
+            slab.total_allocs = AtomicU64::new(self.total_allocs());
+            slab.total_deallocs = AtomicU64::new(self.total_deallocs());
+        }
+        slab.seal();
+        slab
+    }
+}
+
+impl Drop for Slab {
+    /// # Safety
+    /// Deallocates the slab memory using the same layout used during allocation.
+    ///
+    /// Debug builds panic if objects are still live, since releasing the
+    /// memory leaves their pointers dangling. Borrowed memory outlives the
+    /// slab and is exempt, as is a drop during unwinding when `std` is there
+    /// to tell.
+    fn drop(&mut self) {
+        #[cfg(any(test, feature = "std"))]
+        let unwinding = std::thread::panicking();
+        #[cfg(not(any(test, feature = "std")))]
+        let unwinding = false;
+        debug_assert!(
+            self.allocated == 0 || unwinding || matches!(self.backing, Backing::Borrowed),
+            "slab dropped with {} live objects",
+            self.allocated
+        );
+        unsafe {
+            self.backing.release(self.memory, self.size);
+        }
+    }
+}
+
+impl TryFrom<Layout> for Slab {
+    type Error = LayoutToSlabError;
+
+    fn try_from(layout: Layout) -> Result<Self, Self::Error> {
+        Slab::from_layout(layout).ok_or(LayoutToSlabError)
+    }
+}
+
+impl TryFrom<usize> for Slab {
+    type Error = LayoutToSlabError;
+
+    fn try_from(object_size: usize) -> Result<Self, Self::Error> {
+        Slab::new(object_size).ok_or(LayoutToSlabError)
+    }
+}
+
+/// A whole `SLAB_SIZE` region dedicated to one object larger than
+/// `MAX_OBJECT_SIZE`, so [`SlabCache`] can serve the odd sub-page object
+/// without leaving its own accounting.
+pub struct OversizeSlab {
+    memory: NonNull<u8>,
+    object_size: usize,
+}
+
+impl OversizeSlab {
+    /// `None` for sizes a regular slab serves, sizes past `SLAB_SIZE`, or
+    /// when the backing allocation fails.
+    pub fn new(object_size: usize) -> Option<Self> {
+        if object_size <= MAX_OBJECT_SIZE || object_size > SLAB_SIZE {
+            return None;
+        }
+        let memory = Slab::allocate_memory(SLAB_SIZE)?;
+        Some(OversizeSlab { memory, object_size })
+    }
+
+    pub fn as_ptr(&self) -> NonNull<u8> {
+        self.memory
+    }
+
+    pub fn object_size(&self) -> usize {
+        self.object_size
+    }
+
+    pub fn contains(&self, ptr: NonNull<u8>) -> bool {
+        let start = self.memory.as_ptr() as usize;
+        (start..start + SLAB_SIZE).contains(&(ptr.as_ptr() as usize))
+    }
+}
+
+impl Drop for OversizeSlab {
+    fn drop(&mut self) {
+        unsafe { Backing::Heap.release(self.memory, SLAB_SIZE) }
+    }
+}
+
+/// How [`SlabAllocator`] picks the slab serving the next allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlabPolicy {
+    /// First slab with a free slot.
+    #[default]
+    FirstFit,
+    /// Fullest slab that is not full, packing objects into few slabs.
+    BestFit,
+    /// Emptiest slab, spreading objects across slabs.
+    WorstFit,
+}
+
+/// One allocator operation, identified by the slab array index and the slot
+/// it touched so a log stays meaningful across runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Allocate { slab: usize, slot: usize },
+    Deallocate { slab: usize, slot: usize },
+    /// An empty slab dropped by compaction.
+    Release { slab: usize },
+    Clear,
+}
+
+/// Fixed-capacity ring of the latest [`Op`]s of a [`SlabAllocator`]; once
+/// full, each push overwrites the oldest entry.
+#[derive(Debug, Clone)]
+pub struct LogRing {
+    ops: Vec<Op>,
+    capacity: usize,
+    /// Index of the oldest entry once the ring has wrapped.
+    head: usize,
+    overwritten: usize,
+}
+
+impl LogRing {
+    pub fn new(capacity: usize) -> Self {
+        LogRing {
+            ops: Vec::with_capacity(capacity),
+            capacity,
+            head: 0,
+            overwritten: 0,
+        }
+    }
+
+    pub fn push(&mut self, op: Op) {
+        if self.ops.len() < self.capacity {
+            self.ops.push(op);
+        } else if self.capacity != 0 {
+            self.ops[self.head] = op;
+            self.head = (self.head + 1) % self.capacity;
+            self.overwritten += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Entries lost to wrapping. A log that lost none starts from the
+    /// allocator's creation and replays onto a fresh allocator.
+    pub fn overwritten(&self) -> usize {
+        self.overwritten
+    }
+
+    /// Entries from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Op> {
+        let (newer, older) = self.ops.split_at(self.head);
+        older.iter().chain(newer)
+    }
+}
+
+#[derive(Clone)]
+pub struct SlabAllocator {
+    slabs: [Option<Slab>; MAX_SLABS],
+    object_size: usize,
+    hint: CacheHint,
+    allocated: usize,
+    owner_id: u32,
+    policy: SlabPolicy,
+    counters: AllocCounters,
+    peak_allocated: usize,
+    last_error: Option<SlabError>,
+    preferred_node: Option<u32>,
+    /// Empty slabs set aside by [`SlabAllocator::release_empty_slabs`].
+    retained: [Option<Slab>; MAX_RETAINED_SLABS],
+    retain_limit: usize,
+    /// Records every allocation and free once attached.
+    log: Option<LogRing>,
+}
+
+impl SlabAllocator {
+    pub const fn new(object_size: usize) -> Self {
+        const NONE: Option<Slab> = None;
+        SlabAllocator {
+            slabs: [NONE; MAX_SLABS],
+            object_size,
+            hint: CacheHint::Default,
+            allocated: 0,
+            owner_id: 0,
+            policy: SlabPolicy::FirstFit,
+            counters: AllocCounters {
+                alloc_calls: 0,
+                alloc_hits: 0,
+                alloc_misses: 0,
+                free_calls: 0,
+            },
+            peak_allocated: 0,
+            last_error: None,
+            preferred_node: None,
+            retained: [NONE; MAX_RETAINED_SLABS],
+            retain_limit: 0,
+            log: None,
+        }
+    }
+
+    pub const fn new_with_policy(object_size: usize, policy: SlabPolicy) -> Self {
+        let mut allocator = Self::new(object_size);
+        allocator.policy = policy;
+        allocator
+    }
+
+    /// Allocator that keeps up to `retained` empty slabs, at most
+    /// `MAX_RETAINED_SLABS`, when [`SlabAllocator::release_empty_slabs`]
+    /// runs, and reuses them before creating new ones.
+    pub const fn new_with_retention(object_size: usize, retained: usize) -> Self {
+        let mut allocator = Self::new(object_size);
+        allocator.retain_limit = if retained < MAX_RETAINED_SLABS { retained } else { MAX_RETAINED_SLABS };
+        allocator
+    }
+
+    /// [`SlabAllocator::new`] whose size is checked at compile time: sizes
+    /// of 0 or past `MAX_OBJECT_SIZE`, which no slab could serve, fail to
+    /// compile instead of failing every allocation.
+    pub const fn new_const_object_size<const N: usize>() -> Self {
+        const { assert!(N > 0 && N <= MAX_OBJECT_SIZE, "object size must be in 1..=MAX_OBJECT_SIZE") };
+        Self::new(N)
+    }
+
+    pub const fn new_with_owner(object_size: usize, owner: u32) -> Self {
+        let mut allocator = Self::new(object_size);
+        allocator.owner_id = owner;
+        allocator
+    }
+
+    /// Allocator whose new slabs are placed on `preferred_node` with
+    /// [`Slab::new_with_numa_node`], falling back to regular slabs when the
+    /// kernel refuses. NUMA slabs are not coloured.
+    #[cfg(feature = "numa")]
+    pub const fn new_numa_aware(object_size: usize, preferred_node: u32) -> Self {
+        let mut allocator = Self::new(object_size);
+        allocator.preferred_node = Some(preferred_node);
+        allocator
+    }
+
+    /// Allocator whose slabs already hold `min_objects` objects, failing if
+    /// that would take more than `MAX_SLABS` slabs.
+    pub fn with_capacity(object_size: usize, min_objects: usize) -> Result<Self, SlabError> {
+        let capacity = Slab::capacity_for(object_size);
+        if capacity != 0 && min_objects > MAX_SLABS * capacity {
+            return Err(SlabError::SlabArrayFull);
+        }
+        let mut allocator = Self::new(object_size);
+        allocator.try_reserve(min_objects)?;
+        Ok(allocator)
+    }
+
+    /// Allocator starting with `n` empty slabs, capped at `MAX_SLABS`.
+    /// Returns `None` if any of them cannot be created.
+    pub fn with_initial_slabs(object_size: usize, n: usize) -> Option<Self> {
+        let mut allocator = Self::new(object_size);
+        for index in 0..n.min(MAX_SLABS) {
+            allocator.slabs[index] = Some(allocator.new_slab(index).ok()?);
+        }
+        Some(allocator)
+    }
+
+    /// Creates the slab for slot `index`, coloured by its position.
+    fn new_slab(&self, index: usize) -> Result<Slab, SlabError> {
+        if Slab::capacity_for(self.object_size) == 0 {
+            return Err(SlabError::InvalidObjectSize);
+        }
+        #[cfg(feature = "numa")]
+        if let Some(mut slab) = self.preferred_node.and_then(|node| Slab::new_with_numa_node(self.object_size, node)) {
+            slab.hint = self.hint;
+            slab.owner_id = self.owner_id;
+            return Ok(slab);
+        }
+        let color = Slab::color_for(self.object_size, index);
+        let mut slab = Slab::new_colored(self.object_size, Slab::align_size(self.object_size), self.hint, color).ok_or(SlabError::OutOfMemory)?;
+        slab.owner_id = self.owner_id;
+        Ok(slab)
+    }
+
+    /// Hands out a retained empty slab if there is one, else creates one.
+    fn reuse_or_new_slab(&mut self, index: usize) -> Result<Slab, SlabError> {
+        match self.retained.iter_mut().find_map(Option::take) {
+            Some(mut slab) => {
+                slab.set_hint(self.hint);
+                Ok(slab)
+            }
+            None => self.new_slab(index),
+        }
+    }
+
+    /// Takes every empty slab out of the slab array. Up to the retention
+    /// limit they are kept for the next slabs this allocator needs, the rest
+    /// are dropped. Returns how many slabs were dropped.
+    pub fn release_empty_slabs(&mut self) -> usize {
+        let mut released = 0;
+        for index in 0..MAX_SLABS {
+            if !self.slabs[index].as_ref().is_some_and(Slab::is_empty) {
+                continue;
+            }
+            let slab = self.slabs[index].take();
+            match self.retained[..self.retain_limit].iter_mut().find(|spot| spot.is_none()) {
+                Some(spot) => *spot = slab,
+                None => released += 1,
+            }
+        }
+        released
+    }
+
+    pub fn retained_slabs(&self) -> usize {
+        self.retained.iter().flatten().count()
+    }
+
+    /// Starts recording every allocation and free into `log`, replacing
+    /// any log attached before. The log stays readable through
+    /// [`log`](Self::log) while attached, e.g. to dump it on a crash. Compaction and [`clear`](Self::clear) are
+    /// recorded too; [`release_empty_slabs`](Self::release_empty_slabs),
+    /// [`merge_from`](Self::merge_from) and
+    /// [`try_reclaim_to`](Self::try_reclaim_to) are not, so a log taken
+    /// across them does not replay.
+    pub fn attach_log(&mut self, log: LogRing) {
+        self.log = Some(log);
+    }
+
+    pub fn detach_log(&mut self) -> Option<LogRing> {
+        self.log.take()
+    }
+
+    pub fn log(&self) -> Option<&LogRing> {
+        self.log.as_ref()
+    }
+
+    fn record(&mut self, ptr: NonNull<u8>, op: fn(usize, usize) -> Op) {
+        if self.log.is_none() {
+            return;
+        }
+        if let (Some((slab, slot)), Some(log)) = (self.locate(ptr), self.log.as_mut()) {
+            log.push(op(slab, slot));
+        }
+    }
+
+    pub fn owner(&self) -> u32 {
+        self.owner_id
+    }
+
+    pub fn object_size(&self) -> usize {
+        self.object_size
+    }
+
+    /// Size the allocator was created for, e.g. 17 for `SlabAllocator::new(17)`.
+    pub fn object_size_requested(&self) -> usize {
+        self.object_size
+    }
+
+    /// Slot size actually used for each object once rounded up for the free
+    /// list link and alignment, e.g. 24 for `SlabAllocator::new(17)`.
+    pub fn object_size_aligned(&self) -> usize {
+        Slab::align_size(self.object_size)
+    }
+
+    pub fn preferred_numa_node(&self) -> Option<u32> {
+        self.preferred_node
+    }
+
+    pub fn policy(&self) -> SlabPolicy {
+        self.policy
+    }
+
+    fn select_slab(&self) -> Option<usize> {
+        let mut candidates = self
+            .slabs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.as_ref()?)))
+            .filter(|(_, slab)| !slab.is_full());
+
+        let chosen = match self.policy {
+            SlabPolicy::FirstFit => candidates.next(),
+            SlabPolicy::BestFit => {
+                candidates.reduce(|best, next| if next.1.fuller_than(best.1) { next } else { best })
+            }
+            SlabPolicy::WorstFit => {
+                candidates.reduce(|best, next| if best.1.fuller_than(next.1) { next } else { best })
+            }
+        };
+        chosen.map(|(index, _)| index)
+    }
+
+    /// Most-filled slab that can still serve an allocation.
+    pub fn best_fit_slab(&self) -> Option<&Slab> {
+        self.iter()
+            .filter(|slab| !slab.is_full())
+            .reduce(|best, next| if next.fuller_than(best) { next } else { best })
+    }
+
+    pub fn allocate(&mut self) -> Option<NonNull<u8>> {
+        self.allocate_instrumented().map(|(ptr, _)| ptr)
+    }
+
+    /// Like [`SlabAllocator::allocate`], also reporting whether the call had
+    /// to create a new slab.
+    pub fn allocate_instrumented(&mut self) -> Option<(NonNull<u8>, bool)> {
+        self.allocate_hinted(None)
+    }
+
+    fn allocate_hinted(&mut self, hint: Option<CacheHint>) -> Option<(NonNull<u8>, bool)> {
+        self.counters.alloc_calls += 1;
+        let (ptr, grew) = match self.allocate_slot(hint) {
+            Ok(slot) => slot,
+            Err(error) => {
+                self.last_error = Some(error);
+                return None;
+            }
+        };
+        self.last_error = None;
+        self.allocated += 1;
+        self.peak_allocated = self.peak_allocated.max(self.allocated);
+        self.record(ptr, |slab, slot| Op::Allocate { slab, slot });
+        Some((ptr, grew))
+    }
+
+    fn allocate_slot(&mut self, hint: Option<CacheHint>) -> Result<(NonNull<u8>, bool), SlabError> {
+        let take = |slab: &mut Slab| match hint {
+            Some(hint) => slab.allocate_with_hint(hint),
+            None => slab.allocate(),
+        };
+        if let Some(index) = self.select_slab() {
+            if let Some(ptr) = self.slabs[index].as_mut().and_then(take) {
+                self.counters.alloc_hits += 1;
+                return Ok((ptr, false));
+            }
+        }
+
+        let index = self.slabs.iter().position(Option::is_none).ok_or(SlabError::SlabArrayFull)?;
+        self.counters.alloc_misses += 1;
+        let slab = self.reuse_or_new_slab(index)?;
+        let ptr = take(self.slabs[index].insert(slab)).ok_or(SlabError::OutOfMemory)?;
+        Ok((ptr, true))
+    }
+
+    /// Allocates from the slab in slot `index` only, creating it if the slot
+    /// is empty. Returns `None` when that slab is full or `index >= MAX_SLABS`.
+    pub fn allocate_from(&mut self, index: usize) -> Option<NonNull<u8>> {
+        if index >= MAX_SLABS {
+            return None;
+        }
+        self.counters.alloc_calls += 1;
+        if self.slabs[index].is_none() {
+            self.counters.alloc_misses += 1;
+            match self.reuse_or_new_slab(index) {
+                Ok(slab) => self.slabs[index] = Some(slab),
+                Err(error) => {
+                    self.last_error = Some(error);
+                    return None;
+                }
+            }
+        } else if !self.slabs[index].as_ref()?.is_full() {
+            self.counters.alloc_hits += 1;
+        }
+
+        let ptr = self.slabs[index].as_mut()?.allocate()?;
+        self.last_error = None;
+        self.allocated += 1;
+        self.peak_allocated = self.peak_allocated.max(self.allocated);
+        self.record(ptr, |slab, slot| Op::Allocate { slab, slot });
+        Some(ptr)
+    }
+
+    /// Allocates from the slab holding `hint` while it has room, so related
+    /// objects share a page; falls back to [`SlabAllocator::allocate`].
+    pub fn allocate_near(&mut self, hint: NonNull<u8>) -> Option<NonNull<u8>> {
+        let near = self
+            .slabs
+            .iter()
+            .position(|slab| slab.as_ref().is_some_and(|slab| slab.contains(hint) && !slab.is_full()));
+        match near {
+            Some(index) => self.allocate_from(index),
+            None => self.allocate(),
+        }
+    }
+
+    /// [`SlabAllocator::allocate`] with a one-off ordering; see
+    /// [`Slab::allocate_with_hint`]. The allocator's hint is left untouched.
+    pub fn allocate_with_hint(&mut self, hint: CacheHint) -> Option<NonNull<u8>> {
+        self.allocate_hinted(Some(hint)).map(|(ptr, _)| ptr)
+    }
+
+    /// Pointers that are not a live object of this allocator are ignored.
+    pub fn deallocate(&mut self, ptr: NonNull<u8>) {
+        self.counters.free_calls += 1;
+        let Some(slab) = self.iter_mut().find(|slab| slab.is_allocated(ptr)) else {
+            return;
+        };
+        slab.deallocate(ptr);
+        self.allocated = self.allocated.saturating_sub(1);
+        self.record(ptr, |slab, slot| Op::Deallocate { slab, slot });
+    }
+
+    /// Re-runs recorded operations, e.g. [`LogRing::iter`] of a log taken
+    /// from a fresh allocator of the same size. Allocations are made from
+    /// their recorded slab and must land on their recorded slot, and frees
+    /// must hit a live slot; the index
+    /// of the first operation that does not reproduce is returned as the
+    /// error, with every earlier one applied.
+    pub fn replay<'a>(&mut self, ops: impl IntoIterator<Item = &'a Op>) -> Result<(), usize> {
+        for (index, &op) in ops.into_iter().enumerate() {
+            match op {
+                Op::Allocate { slab, slot } => {
+                    let ptr = self.allocate_from(slab).ok_or(index)?;
+                    if self.locate(ptr) != Some((slab, slot)) {
+                        return Err(index);
+                    }
+                }
+                Op::Deallocate { slab, slot } => {
+                    let live = self.slabs.get(slab).and_then(Option::as_ref).and_then(|target| {
+                        target.object_ptr(slot).filter(|&ptr| target.is_allocated(ptr))
+                    });
+                    self.deallocate(live.ok_or(index)?);
+                }
+                Op::Release { slab } => {
+                    if !self.slabs.get(slab).and_then(Option::as_ref).is_some_and(Slab::is_empty) {
+                        return Err(index);
+                    }
+                    self.slabs[slab] = None;
+                }
+                Op::Clear => self.clear(),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    #[cfg(feature = "tags")]
+    pub fn allocate_tagged(&mut self, tag: u32) -> Option<NonNull<u8>> {
+        let ptr = self.allocate()?;
+        let slab = self.iter_mut().find(|slab| slab.contains(ptr))?;
+        let index = slab.slot_index(ptr);
+        slab.tags[index] = tag;
+        Some(ptr)
+    }
+
+    #[cfg(feature = "tags")]
+    pub fn tag_of(&self, ptr: NonNull<u8>) -> Option<u32> {
+        self.iter().find_map(|slab| slab.tag_of(ptr))
+    }
+
+    /// Backing memory of the slabs in use and of the retained empty ones.
+    pub fn memory_reserved(&self) -> usize {
+        self.iter().chain(self.retained.iter().flatten()).map(|slab| slab.size).sum()
+    }
+
+    pub fn total_allocation_bytes(&self) -> usize {
+        self.iter().map(Slab::total_allocation_bytes).sum()
+    }
+
+    pub fn total_capacity_bytes(&self) -> usize {
+        self.iter().map(Slab::total_capacity_bytes).sum()
+    }
+
+    fn has_free_slot(&self) -> bool {
+        self.select_slab().is_some()
+    }
+
+    /// Why the most recent allocation failed; cleared by the next success.
+    pub fn last_error(&self) -> Option<SlabError> {
+        self.last_error
+    }
+
+    pub fn counters(&self) -> AllocCounters {
+        self.counters
+    }
+
+    /// Highest number of objects live at once since the allocator was created.
+    pub fn peak_allocated(&self) -> usize {
+        self.peak_allocated
+    }
+
+    /// Zeroes every [`AllocCounters`] field (`alloc_calls`, `alloc_hits`,
+    /// `alloc_misses`, `free_calls`). The live count and `peak_allocated` are
+    /// kept, as are the slabs themselves.
+    pub fn reset_stats(&mut self) {
+        self.counters = AllocCounters::default();
+    }
+
+    pub fn contains(&self, ptr: NonNull<u8>) -> bool {
+        self.iter().any(|slab| slab.contains(ptr))
+    }
+
+    /// Whether every pointer of `ptrs` lies in one of this allocator's slabs,
+    /// stopping at the first that does not. Meant for asserting ownership
+    /// before a bulk free.
+    pub fn owns_all(&self, ptrs: &[NonNull<u8>]) -> bool {
+        ptrs.iter().all(|&ptr| self.contains(ptr))
+    }
+
+    /// Splits `ptrs` into runs of consecutive pointers that start slots of
+    /// the same slab and yields each run with its slab. Pointers no slab
+    /// owns are skipped. Runs follow the input order, so sort the batch
+    /// first to get a single run per slab.
+    pub fn partition_by_slab<'a>(
+        &'a self,
+        ptrs: &'a [NonNull<u8>],
+    ) -> impl Iterator<Item = (&'a Slab, &'a [NonNull<u8>])> + 'a {
+        let mut rest = ptrs;
+        iter::from_fn(move || loop {
+            let &first = rest.first()?;
+            let Some(slab) = self.iter().find(|slab| slab.is_valid_ptr(first)) else {
+                rest = &rest[1..];
+                continue;
+            };
+            let len = rest.iter().take_while(|&&ptr| slab.is_valid_ptr(ptr)).count();
+            let (run, tail) = rest.split_at(len);
+            rest = tail;
+            return Some((slab, run));
+        })
+    }
+
+    /// Every live object of every slab in ascending address order, so two
+    /// calls without a mutation in between return the same vector. The
+    /// pointers are a snapshot: any allocation or free invalidates it.
+    pub fn collect_allocated(&self) -> Vec<NonNull<u8>> {
+        let mut ptrs: Vec<_> = self.iter().flat_map(Slab::allocated_addresses).collect();
+        ptrs.sort_unstable();
+        ptrs
+    }
+
+    /// `(slab_index, slot_index)` of the slot `ptr` points into, interior
+    /// pointers included. `slab_index` is the position in the slab array, as
+    /// taken by [`SlabAllocator::allocate_from`].
+    pub fn locate(&self, ptr: NonNull<u8>) -> Option<(usize, usize)> {
+        self.slabs.iter().enumerate().find_map(|(index, slab)| {
+            let slot = slab.as_ref()?.slot_containing(ptr)?;
+            Some((index, slot))
+        })
+    }
+
+    pub fn slab_count(&self) -> usize {
+        self.slabs.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    pub fn max_slabs(&self) -> usize {
+        MAX_SLABS
+    }
+
+    pub fn available_slab_slots(&self) -> usize {
+        self.max_slabs() - self.slab_count()
+    }
+
+    /// Most objects this allocator can ever hold: every slab slot filled
+    /// with a full slab. Computed from the object size alone.
+    pub fn max_capacity(&self) -> usize {
+        self.max_slabs() * Slab::capacity_for(self.object_size)
+    }
+
+    /// No slab can be added anymore; existing slabs may still have free slots.
+    pub fn is_exhausted(&self) -> bool {
+        self.available_slab_slots() == 0
+    }
+
+    pub fn iter(&self) -> Slabs<'_> {
+        Slabs {
+            inner: self.slabs.iter(),
+            remaining: self.slab_count(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> SlabsMut<'_> {
+        let remaining = self.slab_count();
+        SlabsMut {
+            inner: self.slabs.iter_mut(),
+            remaining,
+        }
+    }
+
+    pub fn iter_full(&self) -> impl Iterator<Item = &Slab> {
+        self.iter().filter(|slab| slab.is_full())
+    }
+
+    pub fn iter_empty(&self) -> impl Iterator<Item = &Slab> {
+        self.iter().filter(|slab| slab.is_empty())
+    }
+
+    pub fn count_empty_slabs(&self) -> usize {
+        self.iter_empty().count()
+    }
+
+    /// Moves every slab of `other` into free slots of `self`, live objects
+    /// included, and returns how many were moved. Moved slabs adopt this
+    /// allocator's owner and hint. If the slots run out, the slabs that did
+    /// not fit stay in `other` and `PartialMerge` reports the moved count.
+    pub fn merge_from(&mut self, other: &mut SlabAllocator) -> Result<usize, SlabError> {
+        if other.object_size != self.object_size {
+            return Err(SlabError::ObjectSizeMismatch);
+        }
+
+        let mut moved = 0;
+        for source in other.slabs.iter_mut().filter(|slot| slot.is_some()) {
+            let Some(target) = self.slabs.iter_mut().find(|slot| slot.is_none()) else {
+                return Err(SlabError::PartialMerge { moved });
+            };
+            let mut slab = source.take().expect("filtered on occupied slots");
+            slab.owner_id = self.owner_id;
+            slab.set_hint(self.hint);
+            other.allocated -= slab.allocated;
+            self.allocated += slab.allocated;
+            *target = Some(slab);
+            moved += 1;
+        }
+        self.peak_allocated = self.peak_allocated.max(self.allocated);
+        Ok(moved)
+    }
+
+    /// Moves the empty slabs of `self` into free slots of `other`, which
+    /// reuses their memory instead of allocating its own. Moved slabs adopt
+    /// `other`'s owner and hint. Nothing moves between allocators of
+    /// different object sizes. Returns how many slabs were moved.
+    pub fn try_reclaim_to(&mut self, other: &mut SlabAllocator) -> usize {
+        if other.object_size != self.object_size {
+            return 0;
+        }
+
+        let mut moved = 0;
+        for source in self.slabs.iter_mut().filter(|slot| slot.as_ref().is_some_and(Slab::is_empty)) {
+            let Some(target) = other.slabs.iter_mut().find(|slot| slot.is_none()) else {
+                break;
+            };
+            let mut slab = source.take().expect("filtered on occupied slots");
+            slab.owner_id = other.owner_id;
+            slab.set_hint(other.hint);
+            *target = Some(slab);
+            moved += 1;
+        }
+        moved
+    }
+
+    /// Packs the live objects into the lowest-address slabs: objects of the
+    /// highest-address slabs are copied into free slots of the lowest ones,
+    /// then every empty slab past the packed ones is released.
+    ///
+    /// Every pointer to a moved object is invalidated and there is no way to
+    /// learn where it went, so only compact when no pointer into the
+    /// allocator is held, or when the objects are reachable through an
+    /// indirection the caller can rebuild. [`SlabAllocator::compact_with`]
+    /// reports each move instead.
+    pub fn compact(&mut self) -> CompactionReport {
+        let mut order: Vec<usize> = (0..MAX_SLABS).filter(|&index| self.slabs[index].is_some()).collect();
+        order.sort_by_key(|&index| self.slabs[index].as_ref().map(|slab| slab.memory.as_ptr() as usize));
+        self.pack(&order, |_, _| {})
+    }
+
+    /// Empties the sparsest slabs into the densest ones and releases every
+    /// slab left empty. Each object is copied to a new slot, then
+    /// `relocate(old, new)` runs while the old slot still holds it so the
+    /// caller can repoint its references, and only then is the old slot freed.
+    pub fn compact_with(&mut self, relocate: impl FnMut(NonNull<u8>, NonNull<u8>)) -> CompactionReport {
+        let mut order: Vec<usize> = (0..MAX_SLABS).filter(|&index| self.slabs[index].is_some()).collect();
+        order.sort_by_key(|&index| Reverse(self.slabs[index].as_ref().map(|slab| slab.allocated)));
+        self.pack(&order, relocate)
+    }
+
+    /// Moves objects from the slabs at the end of `order` into free slots of
+    /// the slabs at its start, then releases the empty slabs.
+    fn pack(&mut self, order: &[usize], mut relocate: impl FnMut(NonNull<u8>, NonNull<u8>)) -> CompactionReport {
+        let mut report = CompactionReport::default();
+        let (mut low, mut high) = (0, order.len());
+        while low + 1 < high {
+            let Ok([Some(dst), Some(src)]) = self.slabs.get_disjoint_mut([order[low], order[high - 1]]) else {
+                unreachable!("compaction order only holds distinct, populated slots");
+            };
+            if dst.is_full() {
+                low += 1;
+                continue;
+            }
+            let Some(from) = src.allocated_addresses().next() else {
+                high -= 1;
+                continue;
+            };
+
+            let Some(to) = dst.allocate() else {
+                low += 1;
+                continue;
+            };
+            unsafe { ptr::copy_nonoverlapping(from.as_ptr(), to.as_ptr(), dst.object_size) };
+            #[cfg(any(feature = "tags", feature = "ttl"))]
+            let (to_index, from_index) = (dst.slot_index(to), src.slot_index(from));
+            #[cfg(feature = "tags")]
+            {
+                dst.tags[to_index] = src.tags[from_index];
+            }
+            #[cfg(feature = "ttl")]
+            {
+                dst.stamps[to_index] = src.stamps[from_index];
+            }
+            relocate(from, to);
+            if let Some(log) = self.log.as_mut() {
+                log.push(Op::Allocate { slab: order[low], slot: dst.slot_index(to) });
+                log.push(Op::Deallocate { slab: order[high - 1], slot: src.slot_index(from) });
+            }
+            src.deallocate(from);
+            report.objects_moved += 1;
+        }
+
+        for &index in order {
+            if self.slabs[index].as_ref().is_some_and(Slab::is_empty) {
+                let slab = self.slabs[index].take().unwrap();
+                if let Some(log) = self.log.as_mut() {
+                    log.push(Op::Release { slab: index });
+                }
+                report.slabs_freed += 1;
+                report.bytes_reclaimed += slab.size;
+            }
+        }
+        report
+    }
+
+    /// Resets every slab, so all slots are free again while the slabs stay
+    /// allocated. No destructor runs on the objects that were live.
+    pub fn clear(&mut self) {
+        self.iter_mut().for_each(Slab::reset);
+        self.allocated = 0;
+        if let Some(log) = self.log.as_mut() {
+            log.push(Op::Clear);
+        }
+    }
+
+    /// Whether `n` more objects fit, counting slabs that could still be created.
+    pub fn can_allocate(&self, n: usize) -> bool {
+        let free: usize = self.iter().map(Slab::free_count).sum();
+        free + self.available_slab_slots() * Slab::capacity_for(self.object_size) >= n
+    }
+
+    /// Allocates `n` objects or none: capacity is checked and reserved first,
+    /// and anything grabbed is freed again if an allocation still fails.
+    pub fn allocate_n(&mut self, n: usize) -> Option<Vec<NonNull<u8>>> {
+        if !self.can_allocate(n) || self.try_reserve(n).is_err() {
+            return None;
+        }
+        let mut ptrs = Vec::with_capacity(n);
+        for _ in 0..n {
+            match self.allocate() {
+                Some(ptr) => ptrs.push(ptr),
+                None => {
+                    for ptr in ptrs {
+                        self.deallocate(ptr);
+                    }
+                    return None;
+                }
+            }
+        }
+        Some(ptrs)
+    }
+
+    /// Creates slabs up front until `count` more objects fit without growing.
+    /// Slabs created before an error is returned are kept.
+    pub fn try_reserve(&mut self, count: usize) -> Result<(), SlabError> {
+        if Slab::capacity_for(self.object_size) == 0 {
+            return Err(SlabError::InvalidObjectSize);
+        }
+
+        let mut free: usize = self.iter().map(Slab::free_count).sum();
+        while free < count {
+            let index = self.slabs.iter().position(Option::is_none).ok_or(SlabError::SlabArrayFull)?;
+            let slab = self.new_slab(index)?;
+            free += slab.free_count();
+            self.slabs[index] = Some(slab);
+        }
+        Ok(())
+    }
+
+    /// [`Slab::stats`] summed over the active slabs.
+    pub fn stats(&self) -> SlabStats {
+        self.iter().map(Slab::stats).fold(
+            SlabStats {
+                object_size: Slab::align_size(self.object_size),
+                requested_size: self.object_size,
+                ..SlabStats::default()
+            },
+            |total, slab| SlabStats {
+                capacity: total.capacity + slab.capacity,
+                allocated: total.allocated + slab.allocated,
+                free_count: total.free_count + slab.free_count,
+                bytes_allocated: total.bytes_allocated + slab.bytes_allocated,
+                bytes_capacity: total.bytes_capacity + slab.bytes_capacity,
+                ..total
+            },
+        )
+    }
+
+    pub fn per_slab_stats(&self) -> impl Iterator<Item = SlabStats> + '_ {
+        self.iter().map(Slab::stats)
+    }
+
+    /// [`Slab::debug_hexdump`] of every active slab in order.
+    #[cfg(feature = "debug")]
+    pub fn debug_hexdump<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        for slab in self.iter() {
+            slab.debug_hexdump(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn check_no_overlaps(&self) -> bool {
+        for (i, slab) in self.slabs.iter().enumerate() {
+            let Some(slab) = slab else { continue };
+            for other in self.slabs[i + 1..].iter().flatten() {
+                if slab.overlaps(other) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// [`Slab::validate`] on every slab, plus the allocator-level invariants:
+    /// matching object sizes, consistent live count and disjoint slabs.
+    pub fn validate(&self) -> bool {
+        let object_size = Slab::align_size(self.object_size);
+        self.iter().all(|slab| slab.object_size == object_size && slab.validate())
+            && self.iter().map(|slab| slab.allocated).sum::<usize>() == self.allocated
+            && self.check_no_overlaps()
+    }
+
+    /// Highest [`Slab::memory_pressure_score`] among the active slabs, or 0
+    /// without any. Unlike [`SlabAllocator::pressure`], it ignores slab slots
+    /// that are still free.
+    pub fn memory_pressure_score(&self) -> u8 {
+        self.iter().map(Slab::memory_pressure_score).max().unwrap_or(0)
+    }
+
+    /// Fill level scaled to `0..=255`, relative to what the allocator could
+    /// hold with all of its slab slots populated. Computed from counters only.
+    pub fn pressure(&self) -> u8 {
+        let max = MAX_SLABS * Slab::capacity_for(self.object_size);
+        if max == 0 {
+            return u8::MAX;
+        }
+        (self.allocated.min(max) * 255 / max) as u8
+    }
+
+    /// Slabs bucketed by fill level: bucket `i` holds slabs between `i * 10%`
+    /// and `(i + 1) * 10%` full, bucket 10 the completely full ones.
+    pub fn utilisation_histogram(&self) -> [usize; 11] {
+        let mut buckets = [0; 11];
+        for slab in self.iter() {
+            let stats = slab.stats();
+            if let Some(bucket) = (stats.allocated * 10).checked_div(stats.capacity) {
+                buckets[bucket] += 1;
+            }
+        }
+        buckets
+    }
+}
+
+impl TryFrom<usize> for SlabAllocator {
+    type Error = LayoutToSlabError;
+
+    /// Unlike [`SlabAllocator::new`], rejects sizes no slab could ever serve.
+    fn try_from(object_size: usize) -> Result<Self, Self::Error> {
+        if Slab::capacity_for(object_size) == 0 {
+            return Err(LayoutToSlabError);
+        }
+        Ok(SlabAllocator::new(object_size))
+    }
+}
+
+/// Single-threaded [`SlabAllocator`] usable as an [`Allocator`], so boxes
+/// and collections can live in its slabs: `Box::new_in(value, &cell)`.
+#[cfg(feature = "nightly")]
+pub struct SlabAllocatorCell {
+    inner: RefCell<SlabAllocator>,
+}
+
+#[cfg(feature = "nightly")]
+impl SlabAllocatorCell {
+    pub const fn new(allocator: SlabAllocator) -> Self {
+        SlabAllocatorCell {
+            inner: RefCell::new(allocator),
+        }
+    }
+
+    pub fn into_inner(self) -> SlabAllocator {
+        self.inner.into_inner()
+    }
+
+    pub fn allocated(&self) -> usize {
+        self.inner.borrow().allocated()
+    }
+
+    /// Places `T::default()` in a slot and pins it there. Returns `None` when
+    /// `T` does not fit a slot or the allocator is exhausted.
+    ///
+    /// The `Pin` contract holds because slabs never move live objects: the
+    /// slot stays put until the box is dropped and hands it back. The cell
+    /// must be `'static` (e.g. leaked) so that forgetting the box can never
+    /// let the slab memory be released under a pinned value; `Box::into_pin`
+    /// requires as much. This lives on the cell rather than on
+    /// [`SlabAllocator`] because a box only reaches its allocator through
+    /// `&`, and a plain `&SlabAllocator` cannot allocate or free.
+    pub fn allocate_pinned<T: Default>(&'static self) -> Option<Pin<Box<T, &'static Self>>> {
+        Box::try_new_in(T::default(), self).ok().map(Box::into_pin)
+    }
+}
+
+#[cfg(feature = "nightly")]
+unsafe impl Allocator for SlabAllocatorCell {
+    /// Serves layouts that fit one slot at the slot alignment; zero-sized
+    /// layouts get a dangling pointer without touching the slabs.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            let dangling = unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) };
+            return Ok(NonNull::slice_from_raw_parts(dangling, 0));
+        }
+
+        let mut allocator = self.inner.borrow_mut();
+        let slot = Slab::align_size(allocator.object_size);
+        if layout.size() > allocator.object_size
+            || layout.align() > Slab::placement_align()
+            || !slot.is_multiple_of(layout.align())
+        {
+            return Err(AllocError);
+        }
+        let ptr = allocator.allocate().ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            self.inner.borrow_mut().deallocate(ptr);
+        }
+    }
+}
+
+/// Iterator over the active slabs of a [`SlabAllocator`], skipping empty slots.
+pub struct Slabs<'a> {
+    inner: slice::Iter<'a, Option<Slab>>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Slabs<'a> {
+    type Item = &'a Slab;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slab = self.inner.find_map(Option::as_ref)?;
+        self.remaining -= 1;
+        Some(slab)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Slabs<'_> {}
+
+impl FusedIterator for Slabs<'_> {}
+
+/// Mutable iterator over the active slabs of a [`SlabAllocator`].
+pub struct SlabsMut<'a> {
+    inner: slice::IterMut<'a, Option<Slab>>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for SlabsMut<'a> {
+    type Item = &'a mut Slab;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slab = self.inner.find_map(Option::as_mut)?;
+        self.remaining -= 1;
+        Some(slab)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for SlabsMut<'_> {}
+
+impl FusedIterator for SlabsMut<'_> {}
+
+/// `N` sizes from `min` to `max` with a constant ratio between neighbours:
+/// size `i` is the integer `N - 1`-th root of `min^(N - 1 - i) * max^i`,
+/// i.e. `min * (max / min)^(i / (N - 1))` rounded down.
+const fn geometric_tier_sizes<const N: usize>(min: usize, max: usize) -> [usize; N] {
+    let mut sizes = [max; N];
+    if N < 2 {
+        return sizes;
+    }
+    let steps = (N - 1) as u32;
+    let mut i = 0;
+    while i < N {
+        let target = (min as u128)
+            .saturating_pow(steps - i as u32)
+            .saturating_mul((max as u128).saturating_pow(i as u32));
+        let (mut lo, mut hi) = (min, max);
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if (mid as u128).saturating_pow(steps) <= target {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        sizes[i] = lo;
+        i += 1;
+    }
+    sizes
+}
+
+pub struct SlabCache {
+    /// Pointer-sized slots for the smallest requests, so a 4-byte node does
+    /// not take a whole 64-byte slot.
+    tiny: SlabAllocator,
+    small: SlabAllocator,
+    medium: SlabAllocator,
+    large: SlabAllocator,
+    /// One region per live object above `MAX_OBJECT_SIZE`.
+    oversize: [Option<OversizeSlab>; MAX_SLABS],
+    oom_handler: Option<fn()>,
+    memory_limit: usize,
+}
+
+impl SlabCache {
+    pub const fn new() -> Self {
+        const NONE: Option<OversizeSlab> = None;
+        SlabCache {
+            tiny: SlabAllocator::new(TINY_OBJECT_SIZE),
+            small: SlabAllocator::new(SMALL_OBJECT_SIZE),
+            medium: SlabAllocator::new(MEDIUM_OBJECT_SIZE),
+            large: SlabAllocator::new(LARGE_OBJECT_SIZE),
+            oversize: [NONE; MAX_SLABS],
+            oom_handler: None,
+            memory_limit: usize::MAX,
+        }
+    }
+
+    /// Cache dedicated to `T`: the small tier holds exactly one `T` (size
+    /// rounded to its alignment), the medium and large tiers four and sixteen,
+    /// capped at `MAX_OBJECT_SIZE`. The tiny tier is never larger than the
+    /// small one, so a `T` of at most 8 bytes is served from it. Types larger
+    /// than `MAX_OBJECT_SIZE` or zero-sized fail to compile.
+    pub const fn new_for_type<T>() -> Self {
+        const {
+            assert!(mem::size_of::<T>() != 0, "zero-sized types need no slab");
+            assert!(
+                mem::size_of::<T>().next_multiple_of(mem::align_of::<T>()) <= MAX_OBJECT_SIZE,
+                "type too large for a slab"
+            );
+        }
+        let size = mem::size_of::<T>().next_multiple_of(mem::align_of::<T>());
+        let medium = if size * 4 < MAX_OBJECT_SIZE { size * 4 } else { MAX_OBJECT_SIZE };
+        let large = if size * 16 < MAX_OBJECT_SIZE { size * 16 } else { MAX_OBJECT_SIZE };
+        const NONE: Option<OversizeSlab> = None;
+        SlabCache {
+            tiny: SlabAllocator::new(if size < TINY_OBJECT_SIZE { size } else { TINY_OBJECT_SIZE }),
+            small: SlabAllocator::new(size),
+            medium: SlabAllocator::new(medium),
+            large: SlabAllocator::new(large),
+            oversize: [NONE; MAX_SLABS],
+            oom_handler: None,
+            memory_limit: usize::MAX,
+        }
+    }
+
+    /// Cache whose tiers grow geometrically from `min_size` to `max_size`, so
+    /// every tier rounds requests up by at most the same factor: 32, 128 and
+    /// 512 for `32..=512` over three tiers. The `n_tiers` sizes go to the
+    /// largest of the four tiers; the ones left over are capped at `min_size`
+    /// and the tiny object size. More than four tiers, empty ranges and sizes
+    /// past `MAX_OBJECT_SIZE` are refused.
+    pub fn new_for_size_range(min_size: usize, max_size: usize, n_tiers: usize) -> Option<Self> {
+        if !(1..=4).contains(&n_tiers) || min_size == 0 || min_size >= max_size || max_size > MAX_OBJECT_SIZE {
+            return None;
+        }
+        let mut sizes = [min_size.min(TINY_OBJECT_SIZE); 4];
+        let tiers = &mut sizes[4 - n_tiers..];
+        match n_tiers {
+            1 => tiers.copy_from_slice(&geometric_tier_sizes::<1>(min_size, max_size)),
+            2 => tiers.copy_from_slice(&geometric_tier_sizes::<2>(min_size, max_size)),
+            3 => tiers.copy_from_slice(&geometric_tier_sizes::<3>(min_size, max_size)),
+            _ => tiers.copy_from_slice(&geometric_tier_sizes::<4>(min_size, max_size)),
+        }
+        let [tiny, small, medium, large] = sizes;
+        let mut cache = Self::new();
+        cache.tiny = SlabAllocator::new(tiny);
+        cache.small = SlabAllocator::new(small);
+        cache.medium = SlabAllocator::new(medium);
+        cache.large = SlabAllocator::new(large);
+        Some(cache)
+    }
+
+    /// Assembles a cache from prepared tiers, which must be built for the
+    /// 64, 256 and 512 byte classes respectively. The tiny tier starts empty.
+    pub fn with_allocators(
+        small: SlabAllocator,
+        medium: SlabAllocator,
+        large: SlabAllocator,
+    ) -> Result<Self, SlabError> {
+        if small.object_size() != SMALL_OBJECT_SIZE
+            || medium.object_size() != MEDIUM_OBJECT_SIZE
+            || large.object_size() != LARGE_OBJECT_SIZE
+        {
+            return Err(SlabError::ObjectSizeMismatch);
+        }
+        let mut cache = Self::new();
+        cache.small = small;
+        cache.medium = medium;
+        cache.large = large;
+        Ok(cache)
+    }
+
+    /// Cache that never reserves more than `bytes` of slab backing memory.
+    /// Once a tier would need a slab beyond the limit, its allocations fail.
+    pub const fn with_memory_limit(bytes: usize) -> Self {
+        let mut cache = Self::new();
+        cache.memory_limit = bytes;
+        cache
+    }
+
+    /// Tiers from the smallest class up.
+    fn tiers(&self) -> [&SlabAllocator; 4] {
+        [&self.tiny, &self.small, &self.medium, &self.large]
+    }
+
+    /// Backing memory currently held by the slabs of every tier and by
+    /// oversize objects.
+    pub fn memory_reserved(&self) -> usize {
+        let tiers: usize = self.tiers().into_iter().map(SlabAllocator::memory_reserved).sum();
+        tiers + self.oversize_count() * SLAB_SIZE
+    }
+
+    /// Live objects served from an [`OversizeSlab`].
+    pub fn oversize_count(&self) -> usize {
+        self.oversize.iter().flatten().count()
+    }
+
+    /// Dedicates a region to a layout above `MAX_OBJECT_SIZE` and up to
+    /// `SLAB_SIZE`; at most `MAX_SLABS` such objects live at once.
+    fn allocate_oversize(&mut self, layout: Layout) -> Option<NonNull<u8>> {
+        if layout.align() > BACKING_ALIGN || !self.may_grow() {
+            return None;
+        }
+        let spot = self.oversize.iter_mut().find(|spot| spot.is_none())?;
+        let slab = OversizeSlab::new(layout.size())?;
+        let ptr = slab.as_ptr();
+        *spot = Some(slab);
+        Some(ptr)
+    }
+
+    pub fn total_allocation_bytes(&self) -> usize {
+        self.tiers().into_iter().map(SlabAllocator::total_allocation_bytes).sum()
+    }
+
+    pub fn total_capacity_bytes(&self) -> usize {
+        self.tiers().into_iter().map(SlabAllocator::total_capacity_bytes).sum()
+    }
+
+    /// Drops the empty slabs of every tier, keeping only what a tier's own
+    /// retention allows, and returns the bytes released. Tiers cannot trade
+    /// slabs since each fixes its object size, but under a memory limit the
+    /// released bytes become available to whichever tier grows next.
+    pub fn rebalance(&mut self) -> usize {
+        let before = self.memory_reserved();
+        self.tiny.release_empty_slabs();
+        self.small.release_empty_slabs();
+        self.medium.release_empty_slabs();
+        self.large.release_empty_slabs();
+        before - self.memory_reserved()
+    }
+
+    /// Whether every tier's object size tiles its slabs exactly; see
+    /// [`Slab::object_size_is_exact`].
+    pub fn all_tiers_exact(&self) -> bool {
+        self.tiers().iter().all(|tier| Slab::object_size_is_exact(tier.object_size))
+    }
+
+    fn may_grow(&self) -> bool {
+        self.memory_reserved().saturating_add(SLAB_SIZE) <= self.memory_limit
+    }
+
+    /// Installs `handler`, called whenever a tier runs out of slabs or memory.
+    /// Layouts too large for every tier are not reported. The handler runs
+    /// inside the allocation path, so it must not allocate itself.
+    pub fn register_oom_handler(&mut self, handler: fn()) {
+        self.oom_handler = Some(handler);
+    }
+
+    fn notify_oom(&self, ptr: Option<NonNull<u8>>) -> Option<NonNull<u8>> {
+        if ptr.is_none() {
+            if let Some(handler) = self.oom_handler {
+                handler();
+            }
+        }
+        ptr
+    }
+
+    /// Object size of the class that `size` bytes are routed to.
+    fn class_size(&self, size: usize) -> Option<usize> {
+        self.tiers()
+            .into_iter()
+            .map(SlabAllocator::object_size)
+            .find(|&class| size <= class)
+    }
+
+    /// Bytes lost to rounding `layout` up to its class. Zero for layouts no
+    /// class serves.
+    pub fn internal_fragmentation(&self, layout: Layout) -> usize {
+        self.class_size(layout.size()).map_or(0, |class| class - layout.size())
+    }
+
+    fn allocator_for(&mut self, size: usize) -> Option<&mut SlabAllocator> {
+        if size <= self.tiny.object_size {
+            Some(&mut self.tiny)
+        } else if size <= self.small.object_size {
+            Some(&mut self.small)
+        } else if size <= self.medium.object_size {
+            Some(&mut self.medium)
+        } else if size <= self.large.object_size {
+            Some(&mut self.large)
+        } else {
+            None
+        }
+    }
+
+    /// Layouts above `MAX_OBJECT_SIZE` and up to `SLAB_SIZE` get an
+    /// [`OversizeSlab`] of their own.
+    pub fn allocate(&mut self, layout: Layout) -> Option<NonNull<u8>> {
+        if layout.size() > MAX_OBJECT_SIZE && layout.size() <= SLAB_SIZE {
+            let ptr = self.allocate_oversize(layout);
+            return self.notify_oom(ptr);
+        }
+        let may_grow = self.may_grow();
+        let allocator = self.allocator_for(layout.size())?;
+        let ptr = if may_grow || allocator.has_free_slot() {
+            allocator.allocate()
+        } else {
+            None
+        };
+        self.notify_oom(ptr)
+    }
+
+    /// Picks the tier from [`SizeClass::for_size`] instead of the tier
+    /// sizes, one tier per class. With the default
+    /// tiers this routes like [`SlabCache::allocate`]; with custom tiers a
+    /// layout larger than its class tier fails. So does a layout aligned
+    /// beyond [`Slab::slot_align`], whatever [`SizeClass::align_for_class`]
+    /// recommends.
+    pub fn allocate_by_class(&mut self, layout: Layout) -> Option<NonNull<u8>> {
+        if layout.align() > Slab::slot_align() {
+            return None;
+        }
+        let may_grow = self.may_grow();
+        let allocator = match SizeClass::for_size(layout.size())? {
+            SizeClass::Tiny => &mut self.tiny,
+            SizeClass::Small => &mut self.small,
+            SizeClass::Medium => &mut self.medium,
+            SizeClass::Large => &mut self.large,
+        };
+        if layout.size() > allocator.object_size {
+            return None;
+        }
+        let ptr = if may_grow || allocator.has_free_slot() {
+            allocator.allocate()
+        } else {
+            None
+        };
+        self.notify_oom(ptr)
+    }
+
+    pub fn allocate_with_hint(&mut self, layout: Layout, hint: CacheHint) -> Option<NonNull<u8>> {
+        let may_grow = self.may_grow();
+        let allocator = self.allocator_for(layout.size())?;
+        let ptr = if may_grow || allocator.has_free_slot() {
+            allocator.allocate_with_hint(hint)
+        } else {
+            None
+        };
+        self.notify_oom(ptr)
+    }
+
+    /// Arena-style reset: every object of every tier is freed at once and the
+    /// slabs are kept for the next burst, so nothing goes back to the system.
+    /// No destructor runs on the objects that were live. Oversize objects
+    /// have no slab to keep and are released outright.
+    pub fn clear(&mut self) {
+        self.tiny.clear();
+        self.small.clear();
+        self.medium.clear();
+        self.large.clear();
+        for spot in &mut self.oversize {
+            *spot = None;
+        }
+    }
+
+    pub fn deallocate(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        if let Some(allocator) = self.allocator_for(layout.size()) {
+            allocator.deallocate(ptr);
+            return;
+        }
+        let owner = self.oversize.iter_mut().find(|spot| spot.as_ref().is_some_and(|slab| slab.contains(ptr)));
+        if let Some(spot) = owner {
+            *spot = None;
+        }
+    }
+
+    /// Allocates `count` objects of `layout`, all or nothing: on failure the
+    /// objects obtained so far are freed again before returning `None`.
+    pub fn allocate_many(&mut self, layout: Layout, count: usize) -> Option<Vec<NonNull<u8>>> {
+        let mut ptrs = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.allocate(layout) {
+                Some(ptr) => ptrs.push(ptr),
+                None => {
+                    for ptr in ptrs {
+                        self.deallocate(ptr, layout);
+                    }
+                    return None;
+                }
+            }
+        }
+        Some(ptrs)
+    }
+
+    /// # Safety
+    /// `ptr` must be a live allocation of this cache made with `from`; it is
+    /// only freed once its first `to.size()` bytes were copied to the new slot.
+    pub fn downgrade(&mut self, ptr: NonNull<u8>, from: Layout, to: Layout) -> Option<NonNull<u8>> {
+        let moved = self.allocate(to)?;
+        unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), moved.as_ptr(), to.size().min(from.size())) };
+        self.deallocate(ptr, from);
+        Some(moved)
+    }
+
+    pub fn contains(&self, ptr: NonNull<u8>) -> bool {
+        self.tiers().iter().any(|tier| tier.contains(ptr))
+            || self.oversize.iter().flatten().any(|slab| slab.contains(ptr))
+    }
+
+    pub fn allocated(&self) -> usize {
+        self.tiers().into_iter().map(SlabAllocator::allocated).sum::<usize>() + self.oversize_count()
+    }
+
+    /// Validates every tier and checks that no two tiers share backing memory,
+    /// so no pointer can be claimed by more than one class.
+    pub fn validate(&self) -> bool {
+        let tiers = self.tiers();
+        if !tiers.iter().all(|tier| tier.validate()) {
+            return false;
+        }
+        tiers.iter().enumerate().all(|(i, tier)| {
+            tiers[i + 1..].iter().all(|other| {
+                tier.iter().all(|slab| other.iter().all(|other_slab| !slab.overlaps(other_slab)))
+            })
+        })
+    }
+
+    /// [`SlabAllocator::stats`] of the tiny, small, medium and large tiers.
+    pub fn stats_per_tier(&self) -> [SlabStats; 4] {
+        self.tiers().map(SlabAllocator::stats)
+    }
+
+    /// Index into [`SlabCache::stats_per_tier`] of the tier with the highest
+    /// [`SlabAllocator::memory_pressure_score`]; the smaller tier wins ties.
+    pub fn most_pressured_tier(&self) -> usize {
+        let scores = self.tiers().map(SlabAllocator::memory_pressure_score);
+        (0..scores.len()).rev().max_by_key(|&tier| scores[tier]).unwrap()
+    }
+
+    /// [`SlabAllocator::utilisation_histogram`] of the tiny, small, medium
+    /// and large tiers.
+    pub fn utilisation_histograms(&self) -> [[usize; 11]; 4] {
+        self.tiers().map(SlabAllocator::utilisation_histogram)
+    }
+}
+
+impl Default for SlabCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Figures shared by every level of the allocator, so monitoring code can
+/// take a `&dyn AllocatorStats` without knowing which one it watches.
+pub trait AllocatorStats {
+    /// Objects currently handed out.
+    fn live(&self) -> usize;
+    /// Backing memory held, whether or not it is in use.
+    fn reserved_bytes(&self) -> usize;
+    /// Objects the reserved memory can hold without growing.
+    fn capacity(&self) -> usize;
+}
+
+impl AllocatorStats for Slab {
+    fn live(&self) -> usize {
+        self.allocated
+    }
+
+    fn reserved_bytes(&self) -> usize {
+        self.size
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl AllocatorStats for SlabAllocator {
+    fn live(&self) -> usize {
+        self.allocated()
+    }
+
+    fn reserved_bytes(&self) -> usize {
+        self.memory_reserved()
+    }
+
+    fn capacity(&self) -> usize {
+        self.iter().map(|slab| slab.capacity).sum()
+    }
+}
+
+impl AllocatorStats for SlabCache {
+    fn live(&self) -> usize {
+        self.allocated()
+    }
+
+    fn reserved_bytes(&self) -> usize {
+        self.memory_reserved()
+    }
+
+    fn capacity(&self) -> usize {
+        self.tiers().into_iter().map(AllocatorStats::capacity).sum::<usize>() + self.oversize_count()
+    }
+}
+
+/// Arena over a [`SlabCache`] handing out `&mut T` borrowed from the arena,
+/// so no value can outlive it: `let x = arena.alloc_val(42u32);`.
+///
+/// Values are never dropped. Slots are only reclaimed all at once when the
+/// arena goes away, which resets the cache instead of freeing each object.
+pub struct SlabArena {
+    cache: RefCell<SlabCache>,
+}
+
+impl SlabArena {
+    pub const fn new() -> Self {
+        Self::with_cache(SlabCache::new())
+    }
+
+    /// Arena over a prepared cache, e.g. one with a memory limit.
+    pub const fn with_cache(cache: SlabCache) -> Self {
+        SlabArena {
+            cache: RefCell::new(cache),
+        }
+    }
+
+    /// Moves `val` into the arena. Aborts through `handle_alloc_error` when
+    /// the cache cannot serve `T`; see [`SlabArena::try_alloc_val`].
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_val<T>(&self, val: T) -> &mut T {
+        match self.try_alloc_val(val) {
+            Ok(slot) => slot,
+            Err(_) => handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    /// Moves `val` into the arena, or hands it back when `T` is too large
+    /// or too aligned for the cache, or the cache is exhausted.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_alloc_val<T>(&self, val: T) -> Result<&mut T, T> {
+        let layout = Layout::new::<T>();
+        if layout.size() == 0 {
+            mem::forget(val);
+            return Ok(unsafe { &mut *NonNull::<T>::dangling().as_ptr() });
+        }
+        if layout.size() <= MAX_OBJECT_SIZE && layout.align() > Slab::placement_align() {
+            return Err(val);
+        }
+        let Some(ptr) = self.cache.borrow_mut().allocate(layout) else {
+            return Err(val);
+        };
+        // Custom tier sizes and offset free lists can place slots off the
+        // alignment of `T`.
+        if ptr.as_ptr().align_offset(layout.align()) != 0 {
+            self.cache.borrow_mut().deallocate(ptr, layout);
+            return Err(val);
+        }
+        // The slot is fresh, fits `T`, and is aligned for it; no other
+        // reference to it exists until the arena is dropped.
+        let slot = ptr.cast::<T>().as_ptr();
+        unsafe {
+            slot.write(val);
+            Ok(&mut *slot)
+        }
+    }
+
+    pub fn allocated(&self) -> usize {
+        self.cache.borrow().allocated()
+    }
+}
+
+impl Default for SlabArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SlabArena {
+    fn drop(&mut self) {
+        self.cache.get_mut().clear();
+    }
+}
+
+/// `GlobalAlloc` front-end over a spin-locked [`SlabCache`].
+///
+/// Layouts the cache cannot serve (oversized, over-aligned or exhausted) are
+/// forwarded to `alloc::alloc`. Slab backing memory also comes from there, so
+/// this type cannot itself be installed as the `#[global_allocator]`.
+///
+/// With the `thread_cache` feature, freed objects are first parked in a small
+/// per-thread cache and handed out again without taking the lock. Fallback
+/// allocations of served layouts are then rounded up to their class so they
+/// can be recycled the same way.
+pub struct GlobalSlabAllocator {
+    cache: lock::SpinLock,
+    /// Tags the thread caches this allocator fills; 0 until first used.
+    #[cfg(feature = "thread_cache")]
+    id: AtomicUsize,
+}
+
+#[cfg(feature = "thread_cache")]
+static NEXT_ALLOCATOR_ID: AtomicUsize = AtomicUsize::new(1);
+
+unsafe impl Sync for GlobalSlabAllocator {}
+
+impl GlobalSlabAllocator {
+    pub const fn new() -> Self {
+        GlobalSlabAllocator {
+            cache: lock::SpinLock::new(SlabCache::new()),
+            #[cfg(feature = "thread_cache")]
+            id: AtomicUsize::new(0),
+        }
+    }
+
+    #[cfg(feature = "thread_cache")]
+    fn id(&self) -> usize {
+        let id = self.id.load(Ordering::Relaxed);
+        if id != 0 {
+            return id;
+        }
+        let fresh = NEXT_ALLOCATOR_ID.fetch_add(1, Ordering::Relaxed);
+        thread_cache::register(fresh);
+        match self.id.compare_exchange(0, fresh, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => fresh,
+            Err(winner) => {
+                thread_cache::retire(fresh);
+                winner
+            }
+        }
+    }
+
+    /// Index of the thread cache class serving `layout`, in tier order.
+    #[cfg(feature = "thread_cache")]
+    fn class_index(layout: Layout) -> usize {
+        if layout.size() <= TINY_OBJECT_SIZE {
+            0
+        } else if layout.size() <= SMALL_OBJECT_SIZE {
+            1
+        } else if layout.size() <= MEDIUM_OBJECT_SIZE {
+            2
+        } else {
+            3
+        }
+    }
+
+    #[cfg(feature = "thread_cache")]
+    fn class_layout(class: usize) -> Layout {
+        let size = [TINY_OBJECT_SIZE, SMALL_OBJECT_SIZE, MEDIUM_OBJECT_SIZE, LARGE_OBJECT_SIZE][class];
+        Layout::from_size_align(size, Slab::placement_align()).unwrap()
+    }
+
+    /// Layout used with `alloc::alloc` when the cache cannot serve `layout`.
+    fn fallback_layout(layout: Layout) -> Layout {
+        #[cfg(feature = "thread_cache")]
+        if Self::serves(layout) {
+            return Self::class_layout(Self::class_index(layout));
+        }
+        layout
+    }
+
+    /// # Safety
+    /// Returns a parked pointer to the slab it came from, or to `alloc::alloc`
+    /// when it was a class-sized fallback allocation. Call with the lock held.
+    #[cfg(feature = "thread_cache")]
+    unsafe fn release_parked(cache: &mut SlabCache, class: usize, ptr: NonNull<u8>) {
+        let layout = Self::class_layout(class);
+        if cache.contains(ptr) {
+            cache.deallocate(ptr, layout);
+        } else {
+            dealloc(ptr.as_ptr(), layout);
+        }
+    }
+
+    /// Hands the objects parked in the calling thread's cache back to the
+    /// shared cache. Other threads keep theirs until they flush, fill up or
+    /// exit.
+    #[cfg(feature = "thread_cache")]
+    pub fn flush_thread_cache(&self) {
+        let id = self.id();
+        self.with_cache(|cache| {
+            thread_cache::drain(id, None, |class, ptr| unsafe { Self::release_parked(cache, class, ptr) })
+        });
+    }
+
+    /// Runs `f` with the spin lock held; the guard releases it even if `f`
+    /// panics.
+    fn with_cache<R>(&self, f: impl FnOnce(&mut SlabCache) -> R) -> R {
+        let mut cache = self.cache.lock();
+        #[cfg(feature = "thread_cache")]
+        thread_cache::adopt(self.id.load(Ordering::Relaxed), |class, ptr| unsafe {
+            Self::release_parked(&mut cache, class, ptr)
+        });
+        f(&mut cache)
+    }
+
+    /// Number of objects still live in the cache. Objects parked in thread
+    /// caches count as live until flushed.
+    pub fn leak_check(&self) -> usize {
+        self.with_cache(|cache| cache.allocated())
+    }
+
+    /// `(object_size, live objects)` of the tiny, small, medium and large
+    /// classes. The lock is only held to copy the counters; objects parked in
+    /// thread caches count as live, as for [`GlobalSlabAllocator::leak_check`].
+    pub fn class_stats(&self) -> [(usize, usize); 4] {
+        self.with_cache(|cache| cache.tiers().map(|tier| (tier.object_size, tier.allocated)))
+    }
+
+    /// Drops every slab of the cache. Pointers still held by callers dangle
+    /// afterwards; meant for test teardown.
+    pub fn force_reset(&self) {
+        #[cfg(feature = "thread_cache")]
+        self.flush_thread_cache();
+        self.with_cache(|cache| {
+            cache.clear();
+            *cache = SlabCache::new();
+        });
+        #[cfg(feature = "thread_cache")]
+        thread_cache::retire(self.id.swap(0, Ordering::Relaxed));
+    }
+
+    fn serves(layout: Layout) -> bool {
+        layout.size() <= MAX_OBJECT_SIZE && layout.align() <= Slab::placement_align()
+    }
+}
+
+impl Default for GlobalSlabAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "thread_cache")]
+impl Drop for GlobalSlabAllocator {
+    /// Takes back the calling thread's cache and the orphans of exited
+    /// threads. Caches of threads still running are emptied when next used.
+    fn drop(&mut self) {
+        self.flush_thread_cache();
+        thread_cache::retire(*self.id.get_mut());
+    }
+}
+
+unsafe impl GlobalAlloc for GlobalSlabAllocator {
+    /// # Safety
+    /// Caller must ensure the layout is valid. Zero-sized layouts get a dangling,
+    /// well-aligned pointer that must never be dereferenced.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == 0 {
+            return ptr::without_provenance_mut(layout.align());
+        }
+        if Self::serves(layout) {
+            #[cfg(feature = "thread_cache")]
+            if let Some(ptr) = thread_cache::pop(self.id(), Self::class_index(layout)) {
+                return ptr.as_ptr();
+            }
+            if let Some(ptr) = self.with_cache(|cache| cache.allocate(layout)) {
+                return ptr.as_ptr();
+            }
+        }
+        alloc(Self::fallback_layout(layout))
+    }
+
+    /// # Safety
+    /// Pointer must have been allocated with the same layout via alloc.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        #[cfg(feature = "thread_cache")]
+        if let Some(nn) = NonNull::new(ptr).filter(|_| Self::serves(layout)) {
+            let (id, class) = (self.id(), Self::class_index(layout));
+            if !thread_cache::push(id, class, nn) {
+                self.with_cache(|cache| {
+                    thread_cache::drain(id, Some(class), |class, ptr| Self::release_parked(cache, class, ptr));
+                    Self::release_parked(cache, class, nn);
+                });
+            }
+            return;
+        }
+        if let Some(nn) = NonNull::new(ptr).filter(|_| Self::serves(layout)) {
+            let owned = self.with_cache(|cache| {
+                let owned = cache.contains(nn);
+                if owned {
+                    cache.deallocate(nn, layout);
+                }
+                owned
+            });
+            if owned {
+                return;
+            }
+        }
+        dealloc(ptr, Self::fallback_layout(layout));
+    }
+}
+
+/// `GlobalAlloc` front-end over a [`SlabCache`] guarded by any [`Lock`]:
+/// [`lock::SpinLock`] without `std`, `lock::StdMutexLock` with it.
+///
+/// As with [`GlobalSlabAllocator`], layouts the cache cannot serve go to
+/// `alloc::alloc`, which also provides the slab backing memory, so the
+/// wrapper cannot itself be the `#[global_allocator]`.
+pub struct LockedSlabAllocator<L: Lock> {
+    lock: L,
+}
+
+impl<L: Lock> LockedSlabAllocator<L> {
+    pub const fn new(lock: L) -> Self {
+        LockedSlabAllocator { lock }
+    }
+
+    /// Locks the cache for direct use, e.g. to read its statistics.
+    pub fn lock(&self) -> L::Guard<'_> {
+        self.lock.lock()
+    }
+}
+
+unsafe impl<L: Lock> GlobalAlloc for LockedSlabAllocator<L> {
+    /// # Safety
+    /// Caller must ensure the layout is valid. Zero-sized layouts get a dangling,
+    /// well-aligned pointer that must never be dereferenced.
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == 0 {
+            return ptr::without_provenance_mut(layout.align());
+        }
+        if GlobalSlabAllocator::serves(layout) {
+            if let Some(ptr) = self.lock.lock().allocate(layout) {
+                return ptr.as_ptr();
+            }
+        }
+        alloc(layout)
+    }
+
+    /// # Safety
+    /// Pointer must have been allocated with the same layout via alloc.
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        if let Some(nn) = NonNull::new(ptr).filter(|_| GlobalSlabAllocator::serves(layout)) {
+            let mut cache = self.lock.lock();
+            if cache.contains(nn) {
+                cache.deallocate(nn, layout);
+                return;
+            }
+        }
+        dealloc(ptr, layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::cell::Cell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static FAIL_BACKING_ALLOC: Cell<bool> = const { Cell::new(false) };
+    }
+
+    /// Mock backend hook: makes `Slab::allocate_memory` fail on this thread.
+    pub(super) fn backing_alloc_fails() -> bool {
+        FAIL_BACKING_ALLOC.with(Cell::get)
+    }
+
+    #[test]
+    fn test_slab_creation() {
+        let slab = Slab::new(64);
+        assert!(slab.is_some());
+        let slab = slab.unwrap();
+        assert_eq!(slab.object_size, 64);
+        assert!(slab.capacity > 0);
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn test_slab_allocate_deallocate() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptr = slab.allocate();
+        assert!(ptr.is_some());
+        assert!(!slab.is_empty());
+        
+        let ptr = ptr.unwrap();
+        slab.deallocate(ptr);
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn test_slab_multiple_allocations() {
+        let mut slab = Slab::new(64).unwrap();
+        let mut ptrs = Vec::new();
+
+        for _ in 0..10 {
+            if let Some(ptr) = slab.allocate() {
+                ptrs.push(ptr);
+            }
+        }
+
+        assert_eq!(ptrs.len(), 10);
+        assert_eq!(slab.allocated, 10);
+
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn test_slab_full() {
+        let mut slab = Slab::new(64).unwrap();
+        let capacity = slab.capacity;
+        let mut ptrs = Vec::new();
+
+        for _ in 0..capacity {
+            if let Some(ptr) = slab.allocate() {
+                ptrs.push(ptr);
+            }
+        }
+
+        assert!(slab.is_full());
+        assert!(slab.allocate().is_none());
+
+        slab.deallocate(ptrs[0]);
+        assert!(!slab.is_full());
+        slab.reset();
+    }
+
+    #[test]
+    fn test_min_object_size() {
+        assert_eq!(Slab::min_object_size(), mem::size_of::<FreeNode>());
+
+        let slab = Slab::new(17).unwrap();
+        let stats = slab.stats();
+        assert_eq!(stats.requested_size, 17);
+        assert_eq!(stats.object_size, Slab::align_size(17));
+
+        let allocator = SlabAllocator::new(17);
+        assert_eq!(allocator.object_size_requested(), 17);
+        assert_eq!(allocator.object_size_aligned(), Slab::align_size(17));
+        assert_eq!(allocator.stats().object_size, allocator.object_size_aligned());
+
+        let tiny = Slab::new(Slab::min_object_size() - 1);
+        if cfg!(feature = "strict_size") {
+            assert!(tiny.is_none());
+        } else {
+            let stats = tiny.unwrap().stats();
+            assert_eq!(stats.object_size, Slab::align_size(Slab::min_object_size()));
+            assert!(stats.requested_size < stats.object_size);
+        }
+    }
+
+    #[test]
+    fn test_const_capacity() {
+        const POOL_CAPACITY: usize = Slab::capacity_for_object_size(64);
+        const POOL_WASTE: usize = Slab::wasted_bytes_for_object_size(64);
+        assert_eq!((POOL_CAPACITY, POOL_WASTE), (64, 0));
+
+        let slab = Slab::new(200).unwrap();
+        assert_eq!(Slab::capacity_for_object_size(200), slab.stats().capacity);
+        assert_eq!(Slab::wasted_bytes_for_object_size(200), slab.wasted_bytes());
+        assert_eq!(Slab::capacity_for_object_size(512), 8);
+        assert_eq!(Slab::wasted_bytes_for_object_size(512), 0);
+        assert_eq!(Slab::capacity_for_object_size(MAX_OBJECT_SIZE + 1), 0);
+        assert_eq!(Slab::wasted_bytes_for_object_size(0), 0);
+    }
+
+    #[test]
+    fn test_const_object_size() {
+        let mut slab = Slab::new_const::<17>().unwrap();
+        assert_eq!(slab.stride(), Slab::align_size(17));
+        assert_eq!(slab.stats().requested_size, 17);
+        assert_eq!(slab.stats().capacity, Slab::capacity_for_object_size(17));
+        let ptr = slab.allocate().unwrap();
+        slab.deallocate(ptr);
+        assert_eq!(Slab::new_const::<MAX_OBJECT_SIZE>().unwrap().stats().capacity, SLAB_SIZE / MAX_OBJECT_SIZE);
+
+        const POOL: SlabAllocator = SlabAllocator::new_const_object_size::<17>();
+        let mut allocator = SlabAllocator::new_const_object_size::<17>();
+        assert_eq!(allocator.object_size_requested(), 17);
+        assert_eq!(allocator.object_size_aligned(), Slab::align_size(17));
+        let ptr = allocator.allocate().unwrap();
+        allocator.deallocate(ptr);
+        assert_eq!(POOL.object_size(), 17);
+    }
+
+    #[test]
+    fn test_object_size_is_exact() {
+        assert!(Slab::object_size_is_exact(64));
+        assert!(!Slab::object_size_is_exact(48));
+        assert!(!Slab::object_size_is_exact(0));
+        assert!(!Slab::object_size_is_exact(MAX_OBJECT_SIZE + 1));
+        for size in [8, 64, 128, 200, 512] {
+            assert_eq!(Slab::object_size_is_exact(size), Slab::wasted_bytes_for_object_size(size) == 0);
+        }
+
+        assert!(SlabCache::new().all_tiers_exact());
+        assert!(SlabCache::new_for_type::<[u8; 32]>().all_tiers_exact());
+        assert!(!SlabCache::new_for_type::<[u8; 24]>().all_tiers_exact());
+    }
+
+    #[test]
+    fn test_try_from_layout() {
+        let slab = Slab::try_from(Layout::new::<[u64; 4]>()).unwrap();
+        assert_eq!(slab.stats().object_size, 32);
+
+        assert_eq!(Slab::try_from(Layout::from_size_align(64, 64).unwrap()).err(), Some(LayoutToSlabError));
+        assert_eq!(Slab::try_from(Layout::new::<()>()).err(), Some(LayoutToSlabError));
+        assert!(Slab::try_from(MAX_OBJECT_SIZE + 1).is_err());
+        assert!(Slab::try_from(64usize).is_ok());
+
+        assert!(SlabAllocator::try_from(0usize).is_err());
+        let mut allocator = SlabAllocator::try_from(128usize).unwrap();
+        let ptr = allocator.allocate().unwrap();
+        allocator.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_slab_for_min_capacity() {
+        let slab = Slab::new_for_min_capacity(512, 128).unwrap();
+        assert!(slab.capacity >= 128);
+        assert_eq!(slab.size, 64 * 1024);
+        assert_eq!(slab.size % PAGE_SIZE, 0);
+
+        let slab = Slab::new_for_min_capacity(64, 10).unwrap();
+        assert_eq!(slab.size, PAGE_SIZE);
+        assert_eq!(slab.capacity, Slab::capacity_for(64));
+
+        assert!(Slab::new_for_min_capacity(512, MAX_SLAB_SIZE).is_none());
+        assert!(Slab::new_for_min_capacity(0, 1).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "fresh_zeroed")]
+    fn test_allocate_fresh_or_zeroed() {
+        let is_zeroed = |ptr: NonNull<u8>| unsafe {
+            slice::from_raw_parts(ptr.as_ptr(), 64).iter().all(|&b| b == 0)
+        };
+        let mut slab = Slab::new(64).unwrap();
+
+        let fresh = slab.allocate_fresh_or_zeroed().unwrap();
+        assert!(is_zeroed(fresh));
+        unsafe { fresh.as_ptr().write_bytes(0xFF, 64) };
+        slab.deallocate(fresh);
+
+        let dirty = slab.allocate().unwrap();
+        assert_eq!(dirty, fresh);
+        assert!(!is_zeroed(dirty));
+        slab.deallocate(dirty);
+
+        let recycled = slab.allocate_fresh_or_zeroed().unwrap();
+        assert_eq!(recycled, fresh);
+        assert!(is_zeroed(recycled));
+
+        let second = slab.allocate_fresh_or_zeroed().unwrap();
+        assert_ne!(second, fresh);
+        assert!(is_zeroed(second));
+        slab.deallocate(recycled);
+        slab.deallocate(second);
+    }
+
+    #[test]
+    fn test_slab_grow() {
+        let mut slab = Slab::new(64).unwrap();
+        let capacity = slab.capacity;
+        let mut ptrs = Vec::new();
+        for _ in 0..capacity {
+            ptrs.push(slab.allocate().unwrap());
+        }
+        slab.deallocate(ptrs.pop().unwrap());
+        slab.deallocate(ptrs.pop().unwrap());
+        let live = slab.allocated;
+
+        assert!(slab.grow(1));
+        assert_eq!(slab.capacity, 2 * capacity);
+        assert_eq!(slab.free_count(), 2 * capacity - live);
+
+        let mut fresh = Vec::new();
+        while let Some(ptr) = slab.allocate() {
+            assert!(slab.contains(ptr));
+            fresh.push(ptr);
+        }
+        assert_eq!(fresh.len(), 2 * capacity - live);
+        assert!(slab.is_full());
+
+        assert!(!slab.grow(MAX_SLAB_SIZE / PAGE_SIZE));
+        slab.reset();
+    }
+
+    #[test]
+    fn test_slab_split_at() {
+        let mut lower = Slab::new(64).unwrap();
+        let capacity = lower.capacity;
+        let mut ptrs = Vec::new();
+        for i in 0..capacity {
+            let ptr = lower.allocate().unwrap();
+            unsafe { ptr.cast::<u64>().as_ptr().write(i as u64) };
+            ptrs.push(ptr);
+        }
+        for &ptr in ptrs.iter().step_by(2) {
+            lower.deallocate(ptr);
+        }
+
+        let mut upper = lower.split_at(16).unwrap();
+        assert_eq!(lower.capacity, 16);
+        assert_eq!(upper.capacity, capacity - 16);
+        assert_eq!(lower.allocated, 8);
+        assert_eq!(upper.allocated, (capacity - 16) / 2);
+        assert!(!lower.overlaps(&upper));
+        assert!(lower.contains(ptrs[15]) && !lower.contains(ptrs[16]));
+        assert!(upper.contains(ptrs[16]));
+
+        for (i, &ptr) in ptrs.iter().enumerate().skip(1).step_by(2) {
+            assert_eq!(unsafe { ptr.cast::<u64>().as_ptr().read() }, i as u64);
+        }
+
+        let ptr = upper.allocate().unwrap();
+        assert!(upper.contains(ptr));
+        let ptr = lower.allocate().unwrap();
+        assert!(lower.contains(ptr));
+
+        lower.reset();
+        drop(lower);
+        assert!(upper.allocate().is_some());
+        upper.reset();
+        assert!(Slab::new(64).unwrap().split_at(0).is_none());
+    }
+
+    #[test]
+    fn test_slab_contains() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptr = slab.allocate().unwrap();
+        assert!(slab.contains(ptr));
+        
+        let external = NonNull::new(0x1000 as *mut u8).unwrap();
+        assert!(!slab.contains(external));
+        slab.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_slab_overlaps() {
+        let slab = Slab::new(64).unwrap();
+        let other = Slab::new(64).unwrap();
+        assert!(slab.overlaps(&slab));
+        assert!(!slab.overlaps(&other));
+        assert!(!other.overlaps(&slab));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "free list head points outside the slab")]
+    fn test_corrupted_free_list_detected() {
+        let mut slab = Slab::new(64).unwrap();
+        slab.free_list = NonNull::new(0x1000 as *mut FreeNode);
+        slab.allocate();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc_counter")]
+    fn test_alloc_counters() {
+        let mut slab = Slab::new(64).unwrap();
+        for _ in 0..3 {
+            let ptr = slab.allocate().unwrap();
+            slab.deallocate(ptr);
+        }
+        let ptr = slab.allocate().unwrap();
+        assert_eq!(slab.total_allocs(), 4);
+        assert_eq!(slab.total_deallocs(), 3);
+        assert_eq!(slab.allocated, 1);
+
+        slab.reset_counters();
+        assert_eq!(slab.total_allocs(), 0);
+        assert_eq!(slab.total_deallocs(), 0);
+        slab.deallocate(ptr);
+        assert_eq!(slab.total_deallocs(), 1);
+    }
+
+    #[test]
+    fn test_slab_swap() {
+        let mut first = Slab::new(64).unwrap();
+        let mut second = Slab::new(64).unwrap();
+        let a = first.allocate().unwrap();
+        let b = second.allocate().unwrap();
+        let c = second.allocate().unwrap();
+        unsafe {
+            a.cast::<u64>().as_ptr().write(0xAAAA);
+            b.cast::<u64>().as_ptr().write(0xBBBB);
+        }
+
+        first.swap(&mut second);
+
+        assert!(first.contains(b) && first.contains(c));
+        assert!(second.contains(a));
+        assert_eq!(first.allocated, 2);
+        assert_eq!(second.allocated, 1);
+        unsafe {
+            assert_eq!(a.cast::<u64>().as_ptr().read(), 0xAAAA);
+            assert_eq!(b.cast::<u64>().as_ptr().read(), 0xBBBB);
+        }
+
+        second.deallocate(a);
+        first.deallocate(b);
+        first.deallocate(c);
+        assert!(first.is_empty() && second.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "different object sizes")]
+    fn test_slab_swap_mismatched_sizes() {
+        let mut small = Slab::new(64).unwrap();
+        let mut large = Slab::new(256).unwrap();
+        small.swap(&mut large);
+    }
+
+    #[test]
+    fn test_allocator_basic() {
+        let mut allocator = SlabAllocator::new(64);
+        let ptr = allocator.allocate();
+        assert!(ptr.is_some());
+        
+        let ptr = ptr.unwrap();
+        allocator.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_allocator_pressure() {
+        let mut allocator = SlabAllocator::new(64);
+        assert_eq!(allocator.pressure(), 0);
+
+        let max = MAX_SLABS * Slab::capacity_for(64);
+        let mut ptrs = Vec::new();
+        for _ in 0..max / 2 {
+            ptrs.push(allocator.allocate().unwrap());
+        }
+        assert_eq!(allocator.pressure(), 127);
+
+        while let Some(ptr) = allocator.allocate() {
+            ptrs.push(ptr);
+        }
+        assert_eq!(ptrs.len(), max);
+        assert_eq!(allocator.pressure(), 255);
+
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+        assert_eq!(allocator.pressure(), 0);
+        assert_eq!(SlabAllocator::new(0).pressure(), 255);
+    }
+
+    #[test]
+    fn test_allocator_multiple_slabs() {
+        let mut allocator = SlabAllocator::new(64);
+        let mut ptrs = Vec::new();
+
+        for _ in 0..200 {
+            if let Some(ptr) = allocator.allocate() {
+                ptrs.push(ptr);
+            }
+        }
+
+        assert!(ptrs.len() >= 100);
+
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_check_no_overlaps() {
+        let mut allocator = SlabAllocator::new(64);
+        assert!(allocator.check_no_overlaps());
+        let ptrs: Vec<_> = (0..Slab::capacity_for(64) * 3).map(|_| allocator.allocate().unwrap()).collect();
+        assert_eq!(allocator.slabs.iter().flatten().count(), 3);
+        assert!(allocator.check_no_overlaps());
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_owner_round_trip() {
+        let slab = Slab::new_with_owner(64, 7).unwrap();
+        assert_eq!(slab.owner(), 7);
+        assert_eq!(Slab::new(64).unwrap().owner(), 0);
+
+        let mut shard_a = SlabAllocator::new_with_owner(64, 1);
+        let mut shard_b = SlabAllocator::new_with_owner(64, 2);
+        let ptr = shard_a.allocate().unwrap();
+        let other = shard_b.allocate().unwrap();
+        assert_eq!(shard_a.owner(), 1);
+
+        assert!(!shard_b.iter().any(|slab| slab.contains(ptr)));
+        let owner = shard_a
+            .iter()
+            .find(|slab| slab.contains(ptr))
+            .map(Slab::owner);
+        assert_eq!(owner, Some(1));
+        shard_a.deallocate(ptr);
+        shard_b.deallocate(other);
+    }
+
+    #[test]
+    #[cfg(feature = "numa")]
+    fn test_numa_node_slab() {
+        assert_eq!(Slab::new(64).unwrap().numa_node(), None);
+
+        // Containers may forbid mbind; only check the slab when it was granted.
+        if let Some(mut slab) = Slab::new_with_numa_node(64, 0) {
+            assert_eq!(slab.numa_node(), Some(0));
+            let ptr = slab.allocate().unwrap();
+            unsafe { ptr.as_ptr().write_bytes(0xAB, 64) };
+            assert!(slab.contains(ptr));
+            let mut copy = slab.clone();
+            assert_eq!(copy.numa_node(), Some(0));
+            copy.reset();
+            slab.deallocate(ptr);
+        }
+        assert!(Slab::new_with_numa_node(0, 0).is_none());
+
+        let mut allocator = SlabAllocator::new_numa_aware(64, 0);
+        assert_eq!(allocator.preferred_numa_node(), Some(0));
+        assert_eq!(SlabAllocator::new(64).preferred_numa_node(), None);
+        let ptr = allocator.allocate().unwrap();
+        let node = allocator.iter().next().unwrap().numa_node();
+        assert!(node.is_none_or(|node| node == 0));
+        let mut copy = allocator.clone();
+        assert_eq!(copy.preferred_numa_node(), Some(0));
+        assert_eq!(copy.iter().next().unwrap().numa_node(), node);
+        copy.clear();
+        allocator.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_slab_policies() {
+        let capacity = Slab::capacity_for(64);
+
+        // First slab keeps 2 objects, second one is half full.
+        let setup = |policy| {
+            let mut allocator = SlabAllocator::new_with_policy(64, policy);
+            let mut ptrs = Vec::new();
+            for _ in 0..capacity + capacity / 2 {
+                ptrs.push(allocator.allocate().unwrap());
+            }
+            for &ptr in &ptrs[2..capacity] {
+                allocator.deallocate(ptr);
+            }
+            allocator
+        };
+
+        let mut allocator = setup(SlabPolicy::BestFit);
+        assert_eq!(allocator.policy(), SlabPolicy::BestFit);
+        assert_eq!(allocator.best_fit_slab().unwrap().allocated, capacity / 2);
+        allocator.clear();
+
+        let mut best = setup(SlabPolicy::BestFit);
+        let ptr = best.allocate().unwrap();
+        assert!(best.slabs[1].as_ref().unwrap().contains(ptr));
+
+        let mut worst = setup(SlabPolicy::WorstFit);
+        let ptr = worst.allocate().unwrap();
+        assert!(worst.slabs[0].as_ref().unwrap().contains(ptr));
+
+        let mut first = setup(SlabPolicy::FirstFit);
+        let ptr = first.allocate().unwrap();
+        assert!(first.slabs[0].as_ref().unwrap().contains(ptr));
+        for allocator in [&mut best, &mut worst, &mut first] {
+            allocator.clear();
+        }
+    }
+
+    #[test]
+    fn test_allocator_iterators() {
+        let mut allocator = SlabAllocator::new(64);
+        assert_eq!(allocator.iter().len(), 0);
+
+        let capacity = Slab::capacity_for(64);
+        let mut ptrs = Vec::new();
+        for _ in 0..capacity + 1 {
+            ptrs.push(allocator.allocate().unwrap());
+        }
+
+        assert_eq!(allocator.slab_count(), 2);
+        assert_eq!(allocator.iter().len(), 2);
+        assert_eq!(allocator.iter_mut().len(), 2);
+        assert_eq!(allocator.iter_full().count(), 1);
+        assert_eq!(allocator.iter_empty().count(), 0);
+
+        allocator.deallocate(ptrs.pop().unwrap());
+        assert_eq!(allocator.iter_empty().count(), 1);
+
+        for slab in allocator.iter_mut() {
+            slab.set_hint(CacheHint::Fifo);
+        }
+        assert!(allocator.iter().all(|slab| slab.hint() == CacheHint::Fifo));
+
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_alloc_call_counters() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+
+        let ptrs: Vec<_> = (0..capacity + 1).map(|_| allocator.allocate().unwrap()).collect();
+        for &ptr in &ptrs {
+            allocator.deallocate(ptr);
+        }
+
+        assert_eq!(
+            allocator.counters(),
+            AllocCounters {
+                alloc_calls: capacity + 1,
+                alloc_hits: capacity - 1,
+                alloc_misses: 2,
+                free_calls: capacity + 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_allocate_instrumented() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+
+        let grew: Vec<bool> = (0..capacity + 1)
+            .map(|_| allocator.allocate_instrumented().unwrap().1)
+            .collect();
+        assert!(grew[0]);
+        assert!(grew[1..capacity].iter().all(|&grew| !grew));
+        assert!(grew[capacity]);
+        assert_eq!(allocator.allocated(), capacity + 1);
+        allocator.clear();
+    }
+
+    #[test]
+    fn test_is_allocated() {
+        let mut slab = Slab::new(64).unwrap();
+        let a = slab.allocate().unwrap();
+        let b = slab.allocate().unwrap();
+        assert!(slab.is_allocated(a));
+        assert!(slab.is_allocated(b));
+
+        let inner = unsafe { NonNull::new_unchecked(a.as_ptr().add(8)) };
+        assert!(!slab.is_allocated(inner));
+
+        slab.deallocate(a);
+        assert!(!slab.is_allocated(a));
+        assert!(slab.is_allocated(b));
+
+        let mut upper = slab.split_at(1).unwrap();
+        assert!(upper.is_allocated(b));
+        assert!(!slab.is_allocated(b));
+
+        let other = Slab::new(64).unwrap();
+        assert!(!other.is_allocated(b));
+        upper.deallocate(b);
+    }
+
+    #[test]
+    fn test_allocated_set() {
+        let mut slab = Slab::new(64).unwrap();
+        assert_eq!(slab.allocated_set().count(), 0);
+        assert!(slab.object_ptr(slab.stats().capacity).is_none());
+
+        let ptrs: Vec<_> = (0..8).map(|_| slab.allocate().unwrap()).collect();
+        for &ptr in ptrs.iter().step_by(2) {
+            slab.deallocate(ptr);
+        }
+
+        let set: Vec<usize> = slab.allocated_set().collect();
+        assert_eq!(set, [1, 3, 5, 7]);
+
+        let addresses: Vec<_> = slab.allocated_addresses().collect();
+        let expected: Vec<_> = ptrs.iter().skip(1).step_by(2).copied().collect();
+        assert_eq!(addresses, expected);
+        assert_eq!(slab.object_ptr(3), Some(ptrs[3]));
+        for ptr in expected {
+            slab.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_max_contiguous_free() {
+        let mut slab = Slab::new(64).unwrap();
+        let capacity = slab.stats().capacity;
+        assert_eq!(slab.max_contiguous_free(), capacity);
+
+        let ptrs: Vec<_> = (0..capacity).map(|_| slab.allocate().unwrap()).collect();
+        assert_eq!(slab.max_contiguous_free(), 0);
+
+        slab.deallocate(ptrs[1]);
+        for &ptr in &ptrs[4..7] {
+            slab.deallocate(ptr);
+        }
+        assert_eq!(slab.max_contiguous_free(), 3);
+        slab.deallocate(ptrs[capacity - 1]);
+        slab.deallocate(ptrs[capacity - 2]);
+        assert_eq!(slab.max_contiguous_free(), 3);
+        slab.deallocate(ptrs[7]);
+        assert_eq!(slab.max_contiguous_free(), 4);
+
+        for (index, &ptr) in ptrs.iter().enumerate() {
+            if ![1, 4, 5, 6, 7, capacity - 2, capacity - 1].contains(&index) {
+                slab.deallocate(ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let mut cache = SlabCache::with_memory_limit(4 * SLAB_SIZE);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(512, 8).unwrap();
+
+        let larges: Vec<_> = (0..3 * Slab::capacity_for(512)).map(|_| cache.allocate(large).unwrap()).collect();
+        for ptr in larges {
+            cache.deallocate(ptr, large);
+        }
+        let capacity = Slab::capacity_for(64);
+        let smalls = cache.allocate_many(small, capacity).unwrap();
+        assert!(cache.allocate(small).is_none());
+
+        assert_eq!(cache.rebalance(), 3 * SLAB_SIZE);
+        assert_eq!(cache.large.slab_count(), 0);
+        let more = cache.allocate_many(small, 3 * capacity).unwrap();
+        assert!(cache.allocate(small).is_none());
+        assert_eq!(cache.memory_reserved(), 4 * SLAB_SIZE);
+        assert_eq!(cache.rebalance(), 0);
+
+        for ptr in smalls.into_iter().chain(more) {
+            cache.deallocate(ptr, small);
+        }
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let mut cache = SlabCache::with_memory_limit(2 * SLAB_SIZE);
+        let capacity = Slab::capacity_for(64);
+
+        let ptrs: Vec<_> = (0..2 * capacity).map(|_| cache.allocate(layout).unwrap()).collect();
+        assert_eq!(cache.memory_reserved(), 2 * SLAB_SIZE);
+        assert!(cache.allocate(layout).is_none());
+        assert!(cache.allocate(Layout::from_size_align(256, 8).unwrap()).is_none());
+
+        cache.deallocate(ptrs[0], layout);
+        let ptr = cache.allocate(layout).unwrap();
+        assert!(cache.allocate_with_hint(layout, CacheHint::Fifo).is_none());
+        assert_eq!(cache.memory_reserved(), 2 * SLAB_SIZE);
+
+        cache.deallocate(ptr, layout);
+        for &ptr in &ptrs[1..] {
+            cache.deallocate(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn test_slab_slots() {
+        let mut allocator = SlabAllocator::new(512);
+        assert_eq!(allocator.max_slabs(), MAX_SLABS);
+        assert_eq!(allocator.available_slab_slots(), MAX_SLABS);
+
+        allocator.allocate().unwrap();
+        assert_eq!(allocator.available_slab_slots(), MAX_SLABS - 1);
+        assert!(!allocator.is_exhausted());
+
+        while allocator.slab_count() < MAX_SLABS {
+            allocator.allocate().unwrap();
+        }
+        assert_eq!(allocator.available_slab_slots(), 0);
+        assert!(allocator.is_exhausted());
+        allocator.clear();
+    }
+
+    #[test]
+    #[cfg(feature = "debug")]
+    fn test_debug_hexdump() {
+        use std::string::String;
+
+        let mut slab = Slab::new(16).unwrap();
+        let ptr = slab.allocate().unwrap();
+        unsafe { ptr::copy_nonoverlapping(b"slab allocator!".as_ptr(), ptr.as_ptr(), 15) };
+
+        let mut out = String::new();
+        slab.debug_hexdump(&mut out).unwrap();
+        let mut lines = out.lines();
+        assert!(lines.next().unwrap().ends_with("object_size=16 capacity=256 allocated=1"));
+        assert_eq!(lines.next(), Some("slot 0 [alloc]"));
+        assert_eq!(
+            lines.next(),
+            Some("  000000  73 6c 61 62 20 61 6c 6c 6f 63 61 74 6f 72 21 00  |slab allocator!.|")
+        );
+        assert_eq!(lines.next(), Some("slot 1 [free]"));
+
+        let mut allocator = SlabAllocator::new(16);
+        allocator.allocate().unwrap();
+        let mut dump = String::new();
+        allocator.debug_hexdump(&mut dump).unwrap();
+        assert_eq!(dump.lines().filter(|line| line.starts_with("slab ")).count(), 1);
+        slab.deallocate(ptr);
+        allocator.clear();
+    }
+
+    #[test]
+    fn test_allocate_near() {
+        let mut allocator = SlabAllocator::new(512);
+        let capacity = Slab::capacity_for(512);
+        let ptrs: Vec<_> = (0..capacity * 2).map(|_| allocator.allocate().unwrap()).collect();
+        let anchor = ptrs[capacity + 1];
+        allocator.deallocate(ptrs[0]);
+        allocator.deallocate(ptrs[capacity]);
+
+        let near = allocator.allocate_near(anchor).unwrap();
+        assert_eq!(allocator.locate(near).unwrap().0, allocator.locate(anchor).unwrap().0);
+
+        // The anchor's slab is full again: fall back to the first free slot.
+        let fallback = allocator.allocate_near(anchor).unwrap();
+        assert_eq!(fallback, ptrs[0]);
+        let fresh = allocator.allocate_near(NonNull::dangling()).unwrap();
+        assert_eq!(allocator.locate(fresh).unwrap().0, 2);
+
+        for ptr in ptrs[1..capacity].iter().chain(&ptrs[capacity + 1..]).copied().chain([near, fallback, fresh]) {
+            allocator.deallocate(ptr);
+        }
+        assert_eq!(allocator.allocated(), 0);
+    }
+
+    #[test]
+    fn test_allocator_stats_trait() {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let mut slab = Slab::new(64).unwrap();
+        let mut allocator = SlabAllocator::new(64);
+        let mut cache = SlabCache::new();
+        let slab_ptr = slab.allocate().unwrap();
+        let allocator_ptr = allocator.allocate().unwrap();
+        let cache_ptr = cache.allocate(layout).unwrap();
+
+        let monitored: [&dyn AllocatorStats; 3] = [&slab, &allocator, &cache];
+        for stats in monitored {
+            assert_eq!(stats.live(), 1);
+            assert_eq!(stats.reserved_bytes(), SLAB_SIZE);
+            assert_eq!(stats.capacity(), Slab::capacity_for(64));
+        }
+
+        slab.deallocate(slab_ptr);
+        allocator.deallocate(allocator_ptr);
+        cache.deallocate(cache_ptr, layout);
+        assert_eq!(AllocatorStats::live(&cache), 0);
+    }
+
+    #[test]
+    fn test_cache_clear() {
+        let mut cache = SlabCache::new();
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(512, 8).unwrap();
+        let capacity = Slab::capacity_for(64);
+        let first: Vec<_> = (0..capacity + 1).map(|_| cache.allocate(small).unwrap()).collect();
+        cache.allocate(large).unwrap();
+        let reserved = cache.memory_reserved();
+
+        cache.clear();
+        assert_eq!(cache.allocated(), 0);
+        assert_eq!(cache.memory_reserved(), reserved);
+        assert!(cache.validate());
+
+        let second: Vec<_> = (0..capacity + 1).map(|_| cache.allocate(small).unwrap()).collect();
+        assert_eq!(cache.memory_reserved(), reserved);
+        let mut sorted_first = first.clone();
+        let mut sorted_second = second.clone();
+        sorted_first.sort();
+        sorted_second.sort();
+        assert_eq!(sorted_first, sorted_second);
+        for ptr in second {
+            cache.deallocate(ptr, small);
+        }
+    }
+
+    #[test]
+    fn test_slab_retention() {
+        let mut allocator = SlabAllocator::new_with_retention(64, 1);
+        let capacity = Slab::capacity_for(64);
+        let ptrs: Vec<_> = (0..3 * capacity).map(|_| allocator.allocate().unwrap()).collect();
+        let kept = ptrs[3];
+        for &ptr in &ptrs {
+            allocator.deallocate(ptr);
+        }
+
+        assert_eq!(allocator.release_empty_slabs(), 2);
+        assert_eq!(allocator.slab_count(), 0);
+        assert_eq!(allocator.retained_slabs(), 1);
+        assert_eq!(allocator.memory_reserved(), SLAB_SIZE);
+
+        let ptr = allocator.allocate().unwrap();
+        assert_eq!(allocator.retained_slabs(), 0);
+        assert_eq!(allocator.slab_count(), 1);
+        assert!(ptrs.contains(&ptr));
+        assert_eq!(ptr.as_ptr() as usize & !(SLAB_SIZE - 1), kept.as_ptr() as usize & !(SLAB_SIZE - 1));
+        assert_eq!(allocator.release_empty_slabs(), 0);
+        allocator.deallocate(ptr);
+
+        let mut plain = SlabAllocator::new(64);
+        let ptr = plain.allocate().unwrap();
+        assert_eq!(plain.release_empty_slabs(), 0);
+        plain.deallocate(ptr);
+        assert_eq!(plain.release_empty_slabs(), 1);
+        assert_eq!((plain.slab_count(), plain.retained_slabs(), plain.memory_reserved()), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_contains_all() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+        let ptrs: Vec<_> = (0..capacity + 2).map(|_| allocator.allocate().unwrap()).collect();
+        let slab = allocator.slabs[0].as_ref().unwrap();
+
+        assert!(slab.contains_all(&ptrs[..capacity]));
+        assert!(slab.all_valid_ptrs(&ptrs[..capacity]));
+        assert!(!slab.contains_all(&ptrs));
+        assert!(slab.contains_all(&[]));
+        let interior = unsafe { NonNull::new_unchecked(ptrs[3].as_ptr().add(8)) };
+        assert!(slab.contains_all(&[ptrs[0], interior]));
+        assert!(!slab.all_valid_ptrs(&[ptrs[0], interior]));
+
+        assert!(allocator.owns_all(&ptrs));
+        let mut other = SlabAllocator::new(64);
+        let foreign = other.allocate().unwrap();
+        assert!(!allocator.owns_all(&[ptrs[0], foreign]));
+
+        other.deallocate(foreign);
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_partition_by_slab() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+        let ptrs: Vec<_> = (0..2 * capacity).map(|_| allocator.allocate().unwrap()).collect();
+        let mut other = SlabAllocator::new(64);
+        let foreign = other.allocate().unwrap();
+
+        let first = allocator.slabs[0].as_ref().unwrap();
+        let second = allocator.slabs[1].as_ref().unwrap();
+        let interior = unsafe { NonNull::new_unchecked(ptrs[0].as_ptr().add(8)) };
+        let batch = [ptrs[0], ptrs[1], foreign, ptrs[capacity], interior, ptrs[capacity + 1], ptrs[2]];
+        assert_eq!(first.contains_exact_count(&batch), 3);
+        assert_eq!(second.contains_exact_count(&batch), 2);
+
+        let groups: Vec<_> = allocator.partition_by_slab(&batch).collect();
+        assert_eq!(groups.len(), 4);
+        assert!(ptr::eq(groups[0].0, first) && groups[0].1 == &batch[..2]);
+        assert!(ptr::eq(groups[1].0, second) && groups[1].1 == &batch[3..4]);
+        assert!(ptr::eq(groups[2].0, second) && groups[2].1 == &batch[5..6]);
+        assert!(ptr::eq(groups[3].0, first) && groups[3].1 == &batch[6..]);
+
+        let mut sorted = ptrs.clone();
+        sorted.sort_unstable();
+        let runs: Vec<usize> = allocator.partition_by_slab(&sorted).map(|(_, run)| run.len()).collect();
+        assert_eq!(runs, [capacity, capacity]);
+
+        other.deallocate(foreign);
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_collect_allocated() {
+        let mut allocator = SlabAllocator::new(64);
+        assert!(allocator.collect_allocated().is_empty());
+        let capacity = Slab::capacity_for(64);
+        let ptrs: Vec<_> = (0..2 * capacity + 5).map(|_| allocator.allocate().unwrap()).collect();
+        for &ptr in ptrs.iter().step_by(3) {
+            allocator.deallocate(ptr);
+        }
+
+        let live = allocator.collect_allocated();
+        let mut expected: Vec<_> = ptrs.iter().enumerate().filter(|(i, _)| i % 3 != 0).map(|(_, &ptr)| ptr).collect();
+        expected.sort_unstable();
+        assert_eq!(live, expected);
+        assert_eq!(allocator.collect_allocated(), live);
+        assert!(live.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for ptr in live {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        let mut allocator = SlabAllocator::new(200);
+        let capacity = Slab::capacity_for(200);
+        let ptrs: Vec<_> = (0..capacity + 3).map(|_| allocator.allocate().unwrap()).collect();
+
+        assert_eq!(allocator.locate(ptrs[0]), Some((0, 0)));
+        assert_eq!(allocator.locate(ptrs[capacity + 2]), Some((1, 2)));
+        let interior = unsafe { NonNull::new_unchecked(ptrs[5].as_ptr().add(199)) };
+        assert_eq!(allocator.locate(interior), Some((0, 5)));
+
+        // The second slab is coloured: its first bytes hold no slot.
+        let second = allocator.slabs[1].as_ref().unwrap().memory;
+        assert_eq!(allocator.locate(second), None);
+        assert_eq!(allocator.locate(NonNull::dangling()), None);
+
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_geometric_tiers() {
+        assert_eq!(geometric_tier_sizes::<3>(32, 512), [32, 128, 512]);
+        assert_eq!(geometric_tier_sizes::<4>(16, 1024), [16, 64, 256, 1024]);
+        assert_eq!(geometric_tier_sizes::<5>(8, 128), [8, 16, 32, 64, 128]);
+
+        let sizes = geometric_tier_sizes::<4>(10, 500);
+        assert_eq!((sizes[0], sizes[3]), (10, 500));
+        let ratios: Vec<f64> = sizes.windows(2).map(|pair| pair[1] as f64 / pair[0] as f64).collect();
+        assert!(ratios.iter().all(|ratio| (ratio / ratios[0] - 1.0).abs() < 0.05));
+
+        let mut cache = SlabCache::new_for_size_range(32, 512, 3).unwrap();
+        let sizes = [&cache.small, &cache.medium, &cache.large].map(SlabAllocator::object_size);
+        assert_eq!(sizes, [32, 128, 512]);
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let ptr = cache.allocate(layout).unwrap();
+        assert_eq!(cache.medium.allocated(), 1);
+        cache.deallocate(ptr, layout);
+
+        let tiers = |cache: &SlabCache| {
+            [&cache.tiny, &cache.small, &cache.medium, &cache.large].map(SlabAllocator::object_size)
+        };
+        assert_eq!(tiers(&SlabCache::new_for_size_range(16, 512, 4).unwrap()), [16, 50, 161, 512]);
+        assert_eq!(tiers(&SlabCache::new_for_size_range(32, 512, 2).unwrap()), [8, 8, 32, 512]);
+        assert_eq!(tiers(&SlabCache::new_for_size_range(32, 512, 1).unwrap()), [8, 8, 8, 512]);
+        let mut pair = SlabCache::new_for_size_range(32, 512, 2).unwrap();
+        let ptr = pair.allocate(Layout::from_size_align(24, 8).unwrap()).unwrap();
+        assert_eq!(pair.medium.allocated(), 1);
+        pair.deallocate(ptr, Layout::from_size_align(24, 8).unwrap());
+        assert!(SlabCache::new_for_size_range(32, 512, 0).is_none());
+        assert!(SlabCache::new_for_size_range(32, 512, 5).is_none());
+        assert!(SlabCache::new_for_size_range(64, 64, 3).is_none());
+        assert!(SlabCache::new_for_size_range(32, 1024, 3).is_none());
+    }
+
+    #[test]
+    fn test_stats_per_tier() {
+        let mut cache = SlabCache::new();
+        assert_eq!(cache.most_pressured_tier(), 0);
+
+        let small = Layout::from_size_align(48, 8).unwrap();
+        let medium = Layout::from_size_align(256, 8).unwrap();
+        let smalls: Vec<_> = (0..3).map(|_| cache.allocate(small).unwrap()).collect();
+        let mediums: Vec<_> = (0..12).map(|_| cache.allocate(medium).unwrap()).collect();
+
+        let [tiny_stats, small_stats, medium_stats, large_stats] = cache.stats_per_tier();
+        #[cfg(not(feature = "align16"))]
+        assert_eq!(tiny_stats.object_size, 8);
+        #[cfg(feature = "align16")]
+        assert_eq!(tiny_stats.object_size, Slab::align_size(TINY_OBJECT_SIZE));
+        assert_eq!((small_stats.object_size, small_stats.allocated), (64, 3));
+        assert_eq!(small_stats.capacity, Slab::capacity_for(64));
+        assert_eq!(medium_stats.allocated, 12);
+        assert_eq!(medium_stats.free_count, Slab::capacity_for(256) - 12);
+        assert_eq!(medium_stats.bytes_allocated, 12 * 256);
+        assert_eq!(large_stats, SlabStats { object_size: 512, requested_size: 512, ..SlabStats::default() });
+        assert_eq!(cache.most_pressured_tier(), 2);
+
+        for ptr in smalls {
+            cache.deallocate(ptr, small);
+        }
+        for ptr in mediums {
+            cache.deallocate(ptr, medium);
+        }
+    }
+
+    #[test]
+    fn test_tiny_tier() {
+        let mut cache = SlabCache::new();
+        let node = Layout::new::<u32>();
+        let ptrs: Vec<_> = (0..1000).map(|_| cache.allocate(node).unwrap()).collect();
+        assert_eq!(cache.tiny.allocated(), 1000);
+        assert_eq!(cache.small.allocated(), 0);
+        assert!(ptrs.iter().all(|&ptr| cache.tiny.contains(ptr)));
+
+        let [tiny_stats, small_stats, ..] = cache.stats_per_tier();
+        #[cfg(not(feature = "align16"))]
+        {
+            assert_eq!(Slab::capacity_for(TINY_OBJECT_SIZE), 8 * Slab::capacity_for(SMALL_OBJECT_SIZE));
+            assert_eq!(cache.tiny.slab_count(), 2);
+            assert_eq!(tiny_stats.capacity, 2 * Slab::capacity_for(TINY_OBJECT_SIZE));
+        }
+        #[cfg(feature = "align16")]
+        assert_eq!(tiny_stats.capacity, cache.tiny.slab_count() * Slab::capacity_for(TINY_OBJECT_SIZE));
+        assert_eq!(small_stats.capacity, 0);
+        assert_eq!(cache.internal_fragmentation(node), 4);
+
+        for ptr in ptrs {
+            cache.deallocate(ptr, node);
+        }
+        assert!(cache.validate());
+        assert_eq!(SlabCache::new_for_type::<u32>().tiny.object_size(), 4);
+        assert_eq!(SlabCache::new_for_size_range(32, 512, 3).unwrap().tiny.object_size(), 8);
+    }
+
+    #[test]
+    fn test_size_class() {
+        assert_eq!(SizeClass::for_size(0), None);
+        assert_eq!(SizeClass::for_size(1), Some(SizeClass::Tiny));
+        assert_eq!(SizeClass::for_size(8), Some(SizeClass::Tiny));
+        assert_eq!(SizeClass::for_size(9), Some(SizeClass::Small));
+        assert_eq!(SizeClass::for_size(64), Some(SizeClass::Small));
+        assert_eq!(SizeClass::for_size(65), Some(SizeClass::Medium));
+        assert_eq!(SizeClass::for_size(257), Some(SizeClass::Large));
+        assert_eq!(SizeClass::for_size(MAX_OBJECT_SIZE + 1), None);
+        assert_eq!(SizeClass::Small.align_for_class(), 16);
+        assert_eq!(Slab::new(100).unwrap().object_size_class(), Some(SizeClass::Medium));
+        #[cfg(not(any(feature = "strict_size", feature = "align16")))]
+        assert_eq!(Slab::new(4).unwrap().object_size_class(), Some(SizeClass::Tiny));
+
+        let mut cache = SlabCache::new();
+        let sizes = [4, 64, 200, 512];
+        let ptrs: Vec<_> = sizes
+            .iter()
+            .map(|&size| {
+                let align = SizeClass::for_size(size).unwrap().align_for_class().min(Slab::slot_align());
+                cache.allocate_by_class(Layout::from_size_align(size, align).unwrap()).unwrap()
+            })
+            .collect();
+        assert_eq!(cache.tiny.allocated(), 1);
+        assert_eq!(cache.small.allocated(), 1);
+        assert_eq!(cache.medium.allocated(), 1);
+        assert_eq!(cache.large.allocated(), 1);
+        assert!(cache.allocate_by_class(Layout::from_size_align(MAX_OBJECT_SIZE + 1, 8).unwrap()).is_none());
+        let over_aligned = Layout::from_size_align(200, SizeClass::Medium.align_for_class()).unwrap();
+        assert!(cache.allocate_by_class(over_aligned).is_none());
+        assert_eq!(cache.medium.allocated(), 1);
+        for (&size, ptr) in sizes.iter().zip(ptrs) {
+            cache.deallocate(ptr, Layout::from_size_align(size, 8).unwrap());
+        }
+        assert_eq!(cache.allocated(), 0);
+
+        let mut typed = SlabCache::new_for_type::<u32>();
+        assert!(typed.allocate_by_class(Layout::new::<[u8; 12]>()).is_none());
+    }
+
+    #[test]
+    fn test_last_error() {
+        let mut allocator = SlabAllocator::new(512);
+        assert_eq!(allocator.last_error(), None);
+
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(true));
+        let starved = allocator.allocate();
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(false));
+        assert!(starved.is_none());
+        assert_eq!(allocator.last_error(), Some(SlabError::OutOfMemory));
+
+        let ptrs: Vec<_> = (0..allocator.max_capacity()).map(|_| allocator.allocate().unwrap()).collect();
+        assert_eq!(allocator.last_error(), None);
+        assert!(allocator.allocate().is_none());
+        assert_eq!(allocator.last_error(), Some(SlabError::SlabArrayFull));
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+        let ptr = allocator.allocate().unwrap();
+        assert_eq!(allocator.last_error(), None);
+        allocator.deallocate(ptr);
+
+        let mut invalid = SlabAllocator::new(MAX_OBJECT_SIZE + 1);
+        assert!(invalid.allocate().is_none());
+        assert_eq!(invalid.last_error(), Some(SlabError::InvalidObjectSize));
+        assert!(invalid.allocate_from(0).is_none());
+        assert_eq!(invalid.last_error(), Some(SlabError::InvalidObjectSize));
+    }
+
+    #[test]
+    fn test_try_reserve() {
+        let capacity = Slab::capacity_for(256);
+        let mut allocator = SlabAllocator::new(256);
+        assert_eq!(allocator.try_reserve(capacity + 1), Ok(()));
+        assert_eq!(allocator.slab_count(), 2);
+        assert_eq!(allocator.try_reserve(2 * capacity), Ok(()));
+        assert_eq!(allocator.slab_count(), 2);
+
+        assert_eq!(allocator.try_reserve(MAX_SLABS * capacity + 1), Err(SlabError::SlabArrayFull));
+        assert!(allocator.is_exhausted());
+
+        let mut starved = SlabAllocator::new(256);
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(true));
+        let result = starved.try_reserve(1);
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(false));
+        assert_eq!(result, Err(SlabError::OutOfMemory));
+        assert_eq!(starved.slab_count(), 0);
+
+        assert_eq!(SlabAllocator::new(0).try_reserve(1), Err(SlabError::InvalidObjectSize));
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut allocator = SlabAllocator::with_capacity(64, 500).unwrap();
+        assert_eq!(allocator.slab_count(), 500usize.div_ceil(Slab::capacity_for(64)));
+
+        let ptrs: Vec<_> = (0..500)
+            .map(|_| {
+                let (ptr, grew) = allocator.allocate_instrumented().unwrap();
+                assert!(!grew);
+                ptr
+            })
+            .collect();
+        assert_eq!(allocator.slab_count(), 8);
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+
+        let too_many = MAX_SLABS * Slab::capacity_for(64) + 1;
+        assert_eq!(SlabAllocator::with_capacity(64, too_many).err(), Some(SlabError::SlabArrayFull));
+        assert_eq!(SlabAllocator::with_capacity(0, 1).err(), Some(SlabError::InvalidObjectSize));
+    }
+
+    #[test]
+    fn test_with_allocators() {
+        let small = SlabAllocator::with_capacity(64, 100).unwrap();
+        let mut cache = SlabCache::with_allocators(small, SlabAllocator::new(256), SlabAllocator::new(512)).unwrap();
+        assert_eq!(cache.memory_reserved(), 2 * SLAB_SIZE);
+        assert_eq!(cache.allocated(), 0);
+
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        let ptr = cache.allocate(layout).unwrap();
+        assert_eq!(cache.small.slab_count(), 2);
+        cache.deallocate(ptr, layout);
+
+        assert!(matches!(
+            SlabCache::with_allocators(SlabAllocator::new(256), SlabAllocator::new(64), SlabAllocator::new(512)),
+            Err(SlabError::ObjectSizeMismatch)
+        ));
+        assert!(matches!(
+            SlabCache::with_allocators(SlabAllocator::new(64), SlabAllocator::new(256), SlabAllocator::new(500)),
+            Err(SlabError::ObjectSizeMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_new_from_memory() {
+        let mut backing = [0u64; 64];
+        let memory = NonNull::new(backing.as_mut_ptr().cast::<u8>()).unwrap();
+        let mut slab = unsafe { Slab::new_from_memory(memory, 512, 64) }.unwrap();
+        assert_eq!(slab.stats().capacity, 8);
+        assert!(!slab.grow(1));
+
+        let ptrs: Vec<_> = (0..8).map(|_| slab.allocate().unwrap()).collect();
+        assert_eq!(ptrs[0], memory);
+        assert!(slab.is_full());
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+        drop(slab);
+
+        let unaligned = unsafe { NonNull::new_unchecked(memory.as_ptr().add(1)) };
+        assert!(unsafe { Slab::new_from_memory(unaligned, 256, 64) }.is_none());
+        assert!(unsafe { Slab::new_from_memory(memory, 32, 64) }.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "fresh_zeroed")]
+    fn test_new_from_memory_fresh_or_zeroed() {
+        let mut backing = [u64::MAX; 64];
+        let memory = NonNull::new(backing.as_mut_ptr().cast::<u8>()).unwrap();
+        let mut slab = unsafe { Slab::new_from_memory(memory, 512, 64) }.unwrap();
+
+        let ptrs: Vec<_> = (0..8).map(|_| slab.allocate_fresh_or_zeroed().unwrap()).collect();
+        for &ptr in &ptrs {
+            assert!(unsafe { slice::from_raw_parts(ptr.as_ptr(), 64) }.iter().all(|&b| b == 0));
+        }
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "checksum")]
+    fn test_slab_checksum() {
+        let mut slab = Slab::new(64).unwrap();
+        assert!(slab.verify_checksum());
+        let ptrs: Vec<_> = (0..5).map(|_| slab.allocate().unwrap()).collect();
+        slab.deallocate(ptrs[2]);
+        let mut upper = slab.split_at(Slab::capacity_for(64) / 2).unwrap();
+        assert!(slab.verify_checksum() && upper.verify_checksum());
+        let aligned = upper.try_allocate_aligned(128).unwrap();
+        upper.deallocate(aligned);
+        assert!(upper.validate());
+
+        let checksum = slab.checksum();
+        slab.allocated += 1;
+        assert!(!slab.verify_checksum());
+        assert!(!slab.validate());
+        slab.allocated -= 1;
+        assert_eq!(slab.checksum(), checksum);
+
+        let head = slab.free_list;
+        slab.free_list = slab.free_tail;
+        assert!(!slab.verify_checksum());
+        slab.free_list = head;
+        assert!(slab.validate());
+
+        for &ptr in ptrs.iter().filter(|&&ptr| ptr != ptrs[2]) {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.clone().verify_checksum());
+    }
+
+    #[test]
+    #[cfg(not(feature = "align16"))]
+    fn test_try_allocate_aligned() {
+        #[repr(align(64))]
+        struct Backing([u8; 1024]);
+        let mut backing = Backing([0; 1024]);
+        // Slot `k` of 24-byte objects sits at 8 + 24 * k: only k = 5, 13, ... hit 64.
+        let memory = unsafe { NonNull::new_unchecked(backing.0.as_mut_ptr().add(8)) };
+        let mut slab = unsafe { Slab::new_from_memory(memory, 1016, 24) }.unwrap();
+
+        let first = slab.try_allocate_aligned(64).unwrap();
+        assert!((first.as_ptr() as usize).is_multiple_of(64));
+        assert_eq!(first, slab.object_ptr(5).unwrap());
+        let second = slab.try_allocate_aligned(64).unwrap();
+        assert_eq!(second, slab.object_ptr(13).unwrap());
+        assert!(slab.is_allocated(first) && slab.is_allocated(second));
+        assert_eq!(slab.stats().allocated, 2);
+        assert!(slab.validate());
+        assert!(slab.try_allocate_aligned(48).is_none());
+        assert!(slab.try_allocate_aligned(4096).is_none());
+
+        let plain = slab.allocate().unwrap();
+        assert_eq!(plain, memory);
+        for ptr in [first, second, plain] {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.validate());
+    }
+
+    #[test]
+    fn test_raw_parts_round_trip() {
+        let mut slab = Slab::new(48).unwrap();
+        let ptrs: Vec<_> = (0..3).map(|_| slab.allocate().unwrap()).collect();
+        unsafe { ptrs[2].as_ptr().write(0x5A) };
+        slab.deallocate(ptrs[1]);
+
+        let (memory, size, object_size, requested_size, allocated, free_list) = slab.into_raw_parts();
+        assert_eq!((size, object_size, requested_size, allocated), (SLAB_SIZE, 48, 48, 2));
+        assert_eq!(free_list, Some(ptrs[1].cast()));
+
+        let mut slab = unsafe { Slab::from_raw_parts(memory, size, object_size, requested_size, allocated, free_list) };
+        assert!(slab.validate());
+        assert_eq!(slab.stats().allocated, 2);
+        assert!(slab.is_allocated(ptrs[0]) && !slab.is_allocated(ptrs[1]));
+        assert_eq!(unsafe { ptrs[2].as_ptr().read() }, 0x5A);
+        assert_eq!(slab.allocate(), Some(ptrs[1]));
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.validate());
+    }
+
+    #[test]
+    fn test_raw_parts_keep_requested_size() {
+        let slab = Slab::new(17).unwrap();
+        let (memory, size, object_size, requested_size, allocated, free_list) = slab.into_raw_parts();
+        assert_eq!((object_size, requested_size), (Slab::align_size(17), 17));
+
+        let slab = unsafe { Slab::from_raw_parts(memory, size, object_size, requested_size, allocated, free_list) };
+        assert_eq!(slab.stats().requested_size, 17);
+        assert_eq!(slab.stats().object_size, Slab::align_size(17));
+    }
+
+    #[test]
+    #[should_panic(expected = "only uncoloured heap slabs")]
+    fn test_into_raw_parts_coloured() {
+        let _ = Slab::new_with_color(64, 64).unwrap().into_raw_parts();
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptrs: Vec<_> = (0..20).map(|_| slab.allocate().unwrap()).collect();
+        for (index, ptr) in ptrs.iter().enumerate() {
+            unsafe { ptr.cast::<usize>().as_ptr().write(index) };
+        }
+
+        let mut visited = 0;
+        slab.retain(|ptr| {
+            visited += 1;
+            unsafe { ptr.cast::<usize>().as_ptr().read() }.is_multiple_of(2)
+        });
+        assert_eq!(visited, 20);
+        assert_eq!(slab.stats().allocated, 10);
+        assert!(ptrs.iter().enumerate().all(|(index, &ptr)| slab.is_allocated(ptr) == (index % 2 == 0)));
+        assert!(slab.validate());
+
+        slab.retain(|_| false);
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn test_try_shrink() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptrs: Vec<_> = (0..12).map(|_| slab.allocate().unwrap()).collect();
+        assert!(!slab.try_shrink(10));
+
+        for &ptr in &ptrs[5..] {
+            slab.deallocate(ptr);
+        }
+        assert!(!slab.try_shrink(0));
+        assert!(!slab.try_shrink(64));
+        assert!(slab.try_shrink(10));
+
+        let stats = slab.stats();
+        assert_eq!(stats.capacity, 10);
+        assert_eq!(stats.allocated, 5);
+        assert_eq!(stats.free_count, 5);
+
+        let ptrs: Vec<_> = (0..5).map(|_| slab.allocate().unwrap()).collect();
+        assert!(slab.is_full());
+        assert!(slab.allocate().is_none());
+        assert_eq!(slab.allocated_set().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+        slab.reset();
+
+        let mut borrowed = [0u64; 64];
+        let memory = NonNull::new(borrowed.as_mut_ptr().cast::<u8>()).unwrap();
+        let mut borrowed_slab = unsafe { Slab::new_from_memory(memory, 512, 64) }.unwrap();
+        assert!(!borrowed_slab.try_shrink(2));
+    }
+
+    #[test]
+    fn test_backing_is_slab_aligned() {
+        let is_aligned = |slab: &Slab| (slab.memory.as_ptr() as usize).is_multiple_of(SLAB_SIZE);
+
+        let mut slab = Slab::new(64).unwrap();
+        assert!(is_aligned(&slab));
+        let ptr = slab.allocate().unwrap();
+        let base = ptr.as_ptr() as usize & !(SLAB_SIZE - 1);
+        assert_eq!(base, slab.memory.as_ptr() as usize);
+        slab.deallocate(ptr);
+
+        assert!(is_aligned(&Slab::new_for_min_capacity(64, 200).unwrap()));
+        assert!(is_aligned(&slab.clone()));
+        assert!(slab.grow(3));
+        assert!(is_aligned(&slab));
+        assert!(slab.try_shrink(10));
+        assert!(is_aligned(&slab));
+    }
+
+    #[test]
+    fn test_single_slot_slab() {
+        let memory = Slab::allocate_memory(512).unwrap();
+        let mut slab = Slab::from_memory(memory, 512, Backing::Heap, 512);
+        slab.init_free_list();
+        assert_eq!(slab.stats().capacity, 1);
+        assert!(slab.is_empty());
+        assert!(slab.split_at(1).is_none());
+
+        let ptr = slab.allocate().unwrap();
+        assert_eq!(ptr, memory);
+        assert!(slab.is_full());
+        assert!(slab.allocate().is_none());
+
+        slab.deallocate(ptr);
+        assert!(slab.is_empty());
+        #[cfg(feature = "fresh_zeroed")]
+        assert_eq!(slab.allocate_fresh_or_zeroed(), Some(ptr));
+        #[cfg(not(feature = "fresh_zeroed"))]
+        assert_eq!(slab.allocate(), Some(ptr));
+        slab.deallocate(ptr);
+
+        slab.set_hint(CacheHint::Fifo);
+        let ptr = slab.allocate().unwrap();
+        slab.deallocate(ptr);
+        assert_eq!(slab.allocate(), Some(ptr));
+        slab.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_allocate_many() {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let mut cache = SlabCache::new();
+
+        let batch = cache.allocate_many(layout, 100).unwrap();
+        assert_eq!(batch.len(), 100);
+        assert_eq!(cache.allocated(), 100);
+
+        let count = 10;
+        let total = MAX_SLABS * Slab::capacity_for(64);
+        let filler: Vec<_> = (0..total - 100 - (count - 1))
+            .map(|_| cache.allocate(layout).unwrap())
+            .collect();
+        let before = cache.allocated();
+
+        assert!(cache.allocate_many(layout, count).is_none());
+        assert_eq!(cache.allocated(), before);
+        let rest = cache.allocate_many(layout, count - 1).unwrap();
+        assert_eq!(rest.len(), count - 1);
+
+        for ptr in batch.into_iter().chain(filler).chain(rest) {
+            cache.deallocate(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let large = Layout::from_size_align(512, 8).unwrap();
+        let small = Layout::from_size_align(48, 8).unwrap();
+        let mut cache = SlabCache::new();
+
+        let ptr = cache.allocate(large).unwrap();
+        unsafe {
+            for i in 0..large.size() {
+                ptr.as_ptr().add(i).write(i as u8);
+            }
+        }
+
+        let moved = cache.downgrade(ptr, large, small).unwrap();
+        let bytes = unsafe { slice::from_raw_parts(moved.as_ptr(), small.size()) };
+        assert!(bytes.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+        assert!(cache.small.contains(moved));
+        assert_eq!(cache.large.allocated(), 0);
+        assert_eq!(cache.small.allocated(), 1);
+        cache.deallocate(moved, small);
+
+        let kept = cache.allocate(large).unwrap();
+        let filler: Vec<_> = (0..MAX_SLABS * Slab::capacity_for(64))
+            .map(|_| cache.allocate(small).unwrap())
+            .collect();
+        assert!(cache.downgrade(kept, large, small).is_none());
+        assert!(cache.large.slabs.iter().flatten().any(|slab| slab.is_allocated(kept)));
+
+        cache.deallocate(kept, large);
+        for ptr in filler {
+            cache.deallocate(ptr, small);
+        }
+    }
+
+    #[test]
+    fn test_iter_allocated_mut() {
+        let mut slab = Slab::new(16).unwrap();
+        let ptrs: Vec<_> = (0..10).map(|_| slab.allocate().unwrap()).collect();
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            unsafe { ptr.cast::<u64>().as_ptr().write(i as u64) };
+        }
+        slab.deallocate(ptrs[4]);
+
+        for ptr in slab.iter_allocated_mut() {
+            unsafe { *ptr.cast::<u64>().as_ptr() *= 2 };
+        }
+
+        for (i, &ptr) in ptrs.iter().enumerate().filter(|&(i, _)| i != 4) {
+            assert_eq!(unsafe { ptr.cast::<u64>().as_ptr().read() }, 2 * i as u64);
+        }
+        assert_eq!(slab.iter_allocated_mut().count(), 9);
+        slab.reset();
+    }
+
+    #[test]
+    fn test_oom_handler() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static OOM_EVENTS: AtomicUsize = AtomicUsize::new(0);
+        fn on_oom() {
+            OOM_EVENTS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let mut cache = SlabCache::new();
+        cache.register_oom_handler(on_oom);
+
+        let ptrs: Vec<_> = (0..MAX_SLABS * Slab::capacity_for(64))
+            .map(|_| cache.allocate(layout).unwrap())
+            .collect();
+        assert_eq!(OOM_EVENTS.load(Ordering::Relaxed), 0);
+
+        assert!(cache.allocate(layout).is_none());
+        assert_eq!(OOM_EVENTS.load(Ordering::Relaxed), 1);
+        assert!(cache.allocate_with_hint(layout, CacheHint::Fifo).is_none());
+        assert_eq!(OOM_EVENTS.load(Ordering::Relaxed), 2);
+
+        assert!(cache.allocate(Layout::from_size_align(SLAB_SIZE + 1, 8).unwrap()).is_none());
+        assert_eq!(OOM_EVENTS.load(Ordering::Relaxed), 2);
+
+        for ptr in ptrs {
+            cache.deallocate(ptr, layout);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "offset_free_list")]
+    fn test_offset_free_list_density() {
+        assert_eq!(Slab::min_object_size(), 2);
+        let mut slab = Slab::new(4).unwrap();
+        assert_eq!(slab.stats().capacity, SLAB_SIZE / 4);
+
+        let ptrs: Vec<_> = (0..SLAB_SIZE / 4).map(|_| slab.allocate().unwrap()).collect();
+        assert!(slab.allocate().is_none());
+        assert_eq!(ptrs[1].as_ptr() as usize - ptrs[0].as_ptr() as usize, 4);
+        for &ptr in ptrs.iter().rev() {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.validate());
+        assert!(slab.grow(1));
+        assert!(slab.validate());
+        assert!(Slab::from_layout(Layout::from_size_align(6, 4).unwrap()).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "offset_free_list")]
+    fn test_offset_free_list_colored_slabs() {
+        let mut allocator = SlabAllocator::new(10);
+        let count = Slab::capacity_for(10) * 3;
+        let ptrs: Vec<_> = (0..count).map(|_| allocator.allocate().unwrap()).collect();
+        assert_eq!(allocator.slab_count(), 3);
+        assert!(ptrs.iter().all(|ptr| (ptr.as_ptr() as usize).is_multiple_of(mem::align_of::<FreeNode>())));
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+        assert!(allocator.validate());
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_allocate_pinned() {
+        #[derive(Default)]
+        struct Node {
+            value: u64,
+            _pinned: core::marker::PhantomPinned,
+        }
+
+        let cell: &'static SlabAllocatorCell = Box::leak(Box::new(SlabAllocatorCell::new(SlabAllocator::new(64))));
+        let mut node = cell.allocate_pinned::<Node>().unwrap();
+        assert_eq!(node.value, 0);
+        unsafe { node.as_mut().get_unchecked_mut().value = 7 };
+        let addr = &*node as *const Node as *mut u8;
+        assert!(cell.inner.borrow().contains(NonNull::new(addr).unwrap()));
+        assert_eq!(cell.allocated(), 1);
+
+        assert!(cell.allocate_pinned::<[u64; 16]>().is_none());
+        let unit = cell.allocate_pinned::<()>().unwrap();
+        assert_eq!(cell.allocated(), 1);
+
+        drop(unit);
+        drop(node);
+        assert_eq!(cell.allocated(), 0);
+
+        let mut boxed = Box::new_in([1u8; 32], cell);
+        boxed[31] = 2;
+        assert_eq!(cell.allocated(), 1);
+        drop(boxed);
+        assert_eq!(cell.allocated(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "tags")]
+    fn test_allocation_tags() {
+        let mut slab = Slab::new(64).unwrap();
+        let tagged = slab.allocate_tagged(7).unwrap();
+        let plain = slab.allocate().unwrap();
+        assert_eq!(slab.tag_of(tagged), Some(7));
+        assert_eq!(slab.tag_of(plain), Some(0));
+        assert_eq!(slab.tag_of(slab.object_ptr(2).unwrap()), None);
+
+        let dst = slab.object_ptr(3).unwrap();
+        assert!(slab.copy_object_checked(tagged, dst));
+        assert_eq!(slab.tag_of(dst), Some(7));
+        slab.deallocate(tagged);
+        assert_eq!(slab.tag_of(tagged), None);
+        slab.deallocate(plain);
+        slab.deallocate(dst);
+
+        let mut allocator = SlabAllocator::new(512);
+        let ptrs: Vec<_> = (0..20).map(|tag| allocator.allocate_tagged(tag).unwrap()).collect();
+        for (tag, &ptr) in ptrs.iter().enumerate() {
+            assert_eq!(allocator.tag_of(ptr), Some(tag as u32));
+        }
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+        assert_eq!(allocator.tag_of(NonNull::dangling()), None);
+    }
+
+    #[test]
+    #[cfg(feature = "ttl")]
+    fn test_expire() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptrs: Vec<_> = (0..6).map(|_| slab.allocate().unwrap()).collect();
+        let start = Instant::now();
+        for &ptr in &ptrs[..3] {
+            assert!(slab.touch(ptr, start + Duration::from_secs(10)));
+        }
+        assert!(!slab.touch(slab.object_ptr(6).unwrap(), start));
+
+        let mut expired = Vec::new();
+        slab.expire(Duration::from_secs(15), start + Duration::from_secs(20), |ptr| expired.push(ptr));
+        assert_eq!(expired, ptrs[3..]);
+        assert_eq!(slab.allocated, 3);
+        assert!(ptrs[..3].iter().all(|&ptr| slab.is_allocated(ptr)));
+
+        slab.expire(Duration::from_secs(5), start + Duration::from_secs(20), |ptr| expired.push(ptr));
+        assert_eq!(expired.len(), 6);
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut allocator = SlabAllocator::new(512);
+        let capacity = Slab::capacity_for(512);
+        let ptrs: Vec<_> = (0..capacity * 4).map(|_| allocator.allocate().unwrap()).collect();
+        for (index, &ptr) in ptrs.iter().enumerate() {
+            if index % 4 == 0 {
+                unsafe { ptr.cast::<usize>().as_ptr().write(index) };
+            } else {
+                allocator.deallocate(ptr);
+            }
+        }
+        let lowest = allocator.iter().map(|slab| slab.memory).min().unwrap();
+
+        let report = allocator.compact();
+        assert_eq!(report.objects_moved, capacity - capacity / 4);
+        assert_eq!(report.slabs_freed, 3);
+        assert_eq!(report.bytes_reclaimed, 3 * SLAB_SIZE);
+
+        assert_eq!(allocator.slab_count(), 1);
+        assert_eq!(allocator.allocated(), capacity);
+        assert!(allocator.validate());
+        let slab = allocator.iter().next().unwrap();
+        assert_eq!(slab.memory, lowest);
+        let mut markers: Vec<usize> = slab
+            .allocated_addresses()
+            .map(|ptr| unsafe { ptr.cast::<usize>().as_ptr().read() })
+            .collect();
+        markers.sort_unstable();
+        assert_eq!(markers, (0..capacity * 4).step_by(4).collect::<Vec<_>>());
+
+        assert_eq!(allocator.compact(), CompactionReport::default());
+        let live: Vec<_> = allocator.iter().next().unwrap().allocated_addresses().collect();
+        for ptr in live {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_copy_object() {
+        let mut slab = Slab::new(64).unwrap();
+        let a = slab.allocate().unwrap();
+        let b = slab.allocate().unwrap();
+        unsafe { a.as_ptr().write_bytes(0x11, 64) };
+        unsafe { b.as_ptr().write_bytes(0x22, 64) };
+
+        assert!(slab.copy_object(a, b));
+        assert_eq!(unsafe { *b.as_ptr().add(63) }, 0x11);
+        assert!(slab.copy_object(a, a));
+        let inside = unsafe { NonNull::new_unchecked(a.as_ptr().add(8)) };
+        assert!(!slab.copy_object(inside, b));
+        assert!(!slab.copy_object(a, NonNull::dangling()));
+
+        let free = slab.object_ptr(5).unwrap();
+        assert!(!slab.copy_object_checked(a, b));
+        assert!(!slab.copy_object_checked(free, a));
+        assert!(slab.copy_object_checked(a, free));
+        assert!(slab.is_allocated(free));
+        assert_eq!(slab.stats().allocated, 3);
+        assert_eq!(unsafe { *free.as_ptr() }, 0x11);
+        assert!(slab.validate());
+
+        for ptr in [a, b, free] {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.validate());
+    }
+
+    #[test]
+    fn test_new_with_order() {
+        fn first_slots(order: InitOrder) -> Vec<usize> {
+            let mut slab = Slab::new_with_order(64, order).unwrap();
+            let base = slab.memory.as_ptr() as usize;
+            let ptrs: Vec<_> = (0..4).map(|_| slab.allocate().unwrap()).collect();
+            let slots = ptrs.iter().map(|ptr| (ptr.as_ptr() as usize - base) / 64).collect();
+            for ptr in ptrs {
+                slab.deallocate(ptr);
+            }
+            slots
+        }
+
+        let capacity = Slab::capacity_for(64);
+        assert_eq!(first_slots(InitOrder::Ascending), [0, 1, 2, 3]);
+        assert_eq!(first_slots(InitOrder::Descending), [capacity - 1, capacity - 2, capacity - 3, capacity - 4]);
+        assert_eq!(first_slots(InitOrder::Strided(16)), [0, 16, 32, 48]);
+
+        let mut slab = Slab::new_with_order(64, InitOrder::Strided(16)).unwrap();
+        assert!(slab.validate());
+        let ptrs: Vec<_> = (0..capacity).map(|_| slab.allocate().unwrap()).collect();
+        let base = slab.memory.as_ptr() as usize;
+        assert_eq!((ptrs[capacity / 16].as_ptr() as usize - base) / 64, 1);
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_deallocate_secure() {
+        let mut slab = Slab::new(64).unwrap();
+        let header = mem::size_of::<FreeNode>();
+        let secret = slab.allocate().unwrap();
+        let plain = slab.allocate().unwrap();
+        for &ptr in &[secret, plain] {
+            unsafe { ptr.as_ptr().write_bytes(0xA5, 64) };
+        }
+
+        slab.deallocate_secure(secret);
+        slab.deallocate(plain);
+        let scrubbed = unsafe { slice::from_raw_parts(secret.as_ptr(), 64) };
+        assert!(scrubbed[header..].iter().all(|&byte| byte == 0));
+        let stale = unsafe { slice::from_raw_parts(plain.as_ptr(), 64) };
+        assert!(stale[header..].iter().all(|&byte| byte == 0xA5));
+        assert!(slab.validate());
+        assert_eq!(slab.stats().allocated, 0);
+    }
+
+    #[test]
+    fn test_write_zeros_free_slots() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptrs: Vec<_> = (0..4).map(|_| slab.allocate().unwrap()).collect();
+        for &ptr in &ptrs {
+            unsafe { ptr.as_ptr().write_bytes(0xA5, 64) };
+        }
+        slab.deallocate(ptrs[0]);
+        slab.deallocate(ptrs[2]);
+
+        slab.write_zeros_free_slots();
+        let header = mem::size_of::<FreeNode>();
+        for (index, &ptr) in ptrs.iter().enumerate() {
+            let bytes = unsafe { slice::from_raw_parts(ptr.as_ptr(), 64) };
+            if index % 2 == 0 {
+                assert!(bytes[header..].iter().all(|&byte| byte == 0));
+            } else {
+                assert!(bytes.iter().all(|&byte| byte == 0xA5));
+            }
+        }
+        assert!(slab.validate());
+        assert_eq!(slab.allocate(), Some(ptrs[2]));
+        assert_eq!(slab.allocate(), Some(ptrs[0]));
+        for &ptr in &ptrs {
+            slab.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_new_interleaved() {
+        let mut slab = Slab::new_interleaved(24, CACHE_LINE_SIZE).unwrap();
+        assert_eq!(slab.stride(), CACHE_LINE_SIZE);
+        assert_eq!(slab.stats().object_size, Slab::align_size(24));
+        assert_eq!(slab.stats().capacity, SLAB_SIZE / CACHE_LINE_SIZE);
+        assert_eq!(slab.stats().requested_size, 24);
+
+        let ptrs: Vec<_> = (0..4).map(|_| slab.allocate().unwrap()).collect();
+        assert_eq!(slab.total_allocation_bytes(), 4 * Slab::align_size(24));
+        for pair in ptrs.windows(2) {
+            assert_eq!(pair[1].as_ptr() as usize - pair[0].as_ptr() as usize, CACHE_LINE_SIZE);
+        }
+        assert!(!slab.is_valid_ptr(unsafe { NonNull::new_unchecked(ptrs[0].as_ptr().add(24)) }));
+        let mut copy = slab.clone();
+        assert!(copy.validate());
+        assert_eq!((copy.stride(), copy.stats().object_size), (CACHE_LINE_SIZE, Slab::align_size(24)));
+        copy.reset();
+        let wide = Slab::new_interleaved(100, 1024).unwrap();
+        assert_eq!((wide.stride(), wide.object_size_class()), (1024, Some(SizeClass::Medium)));
+        for &ptr in &ptrs {
+            slab.deallocate(ptr);
+        }
+        assert!(slab.validate());
+        assert_eq!(Slab::new(24).unwrap().stride(), Slab::align_size(24));
+
+        assert!(Slab::new_interleaved(24, 16).is_none());
+        assert!(Slab::new_interleaved(24, 36).is_none());
+        assert!(Slab::new_interleaved(24, SLAB_SIZE + 8).is_none());
+    }
+
+    #[test]
+    fn test_power_of_two_stride_lookup() {
+        for (slab, shift) in [
+            (Slab::new(64).unwrap(), Some(6)),
+            (Slab::new_with_color(256, 64).unwrap(), Some(8)),
+            (Slab::new(48).unwrap(), None),
+            (Slab::new_interleaved(24, 96).unwrap(), None),
+        ] {
+            assert_eq!(slab.stride_shift, shift);
+            assert_eq!(slab.clone().stride_shift, shift);
+            let mut slab = slab;
+            let ptrs: Vec<_> = (0..slab.capacity).map(|_| slab.allocate().unwrap()).collect();
+            for (index, &ptr) in ptrs.iter().enumerate() {
+                assert_eq!(slab.slot_index(ptr), index);
+                assert!(slab.is_allocated(ptr));
+                let inner = unsafe { NonNull::new_unchecked(ptr.as_ptr().add(8)) };
+                assert_eq!(slab.slot_containing(inner), Some(index));
+                assert!(!slab.is_valid_ptr(inner));
+            }
+            for &ptr in ptrs.iter().step_by(2) {
+                slab.deallocate(ptr);
+            }
+            assert!(slab.validate());
+            for &ptr in ptrs.iter().skip(1).step_by(2) {
+                slab.deallocate(ptr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_slab_coloring() {
+        let mut slab = Slab::new_with_color(64, 64).unwrap();
+        assert_eq!(slab.stats().capacity, Slab::capacity_for(64) - 1);
+        let ptr = slab.allocate().unwrap();
+        assert_eq!(ptr.as_ptr() as usize - slab.memory.as_ptr() as usize, 64);
+        assert!(slab.is_allocated(ptr));
+        assert!(slab.validate());
+        slab.deallocate(ptr);
+        #[cfg(not(feature = "align16"))]
+        assert_eq!(Slab::new_with_color(100, 40).unwrap().stats().capacity, (SLAB_SIZE - 40) / Slab::align_size(100));
+
+        assert!(Slab::new_with_color(64, 4).is_none());
+        assert!(Slab::new_with_color(64, SLAB_SIZE - 32).is_none());
+
+        // 200-byte slots leave 96 spare bytes: colours 0, 64, 32, 0, ...
+        #[cfg(not(feature = "align16"))]
+        assert_allocator_colors(200, [0, 64, 32]);
+    }
+
+    /// Fills three slabs of `object_size` objects and checks their colours.
+    #[cfg(any(not(feature = "align16"), not(feature = "offset_free_list")))]
+    fn assert_allocator_colors(object_size: usize, expected: [usize; 3]) {
+        let mut allocator = SlabAllocator::new(object_size);
+        let capacity = Slab::capacity_for(object_size);
+        let ptrs: Vec<_> = (0..capacity * 3).map(|_| allocator.allocate().unwrap()).collect();
+        let colors: Vec<_> = allocator.iter().map(|slab| slab.color).collect();
+        assert_eq!(colors, expected);
+        assert!(allocator.iter().all(|slab| slab.stats().capacity == capacity));
+        assert!(allocator.validate());
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_utilisation_histogram() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+
+        for _ in 0..capacity * 3 {
+            allocator.allocate().unwrap();
+        }
+        let mut expected = [0; 11];
+        expected[10] = 3;
+        assert_eq!(allocator.utilisation_histogram(), expected);
+
+        let ptr = allocator.allocate().unwrap();
+        expected[0] = 1;
+        assert_eq!(allocator.utilisation_histogram(), expected);
+        allocator.deallocate(ptr);
+
+        allocator.clear();
+
+        let mut cache = SlabCache::new();
+        cache.allocate(Layout::from_size_align(200, 8).unwrap()).unwrap();
+        let histograms = cache.utilisation_histograms();
+        assert_eq!(histograms[1], [0; 11]);
+        assert_eq!(histograms[2][0], 1);
+        assert_eq!(histograms[3], [0; 11]);
+        cache.clear();
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    fn test_decommit() {
+        let mut slab = Slab::new_for_min_capacity(64, 256).unwrap();
+        let capacity = slab.stats().capacity;
+
+        let ptr = slab.allocate().unwrap();
+        assert!(!slab.decommit());
+        slab.deallocate(ptr);
+
+        let ptrs: Vec<_> = (0..capacity).map(|_| slab.allocate().unwrap()).collect();
+        for &ptr in &ptrs {
+            unsafe { ptr.as_ptr().write_bytes(0xAB, 64) };
+            slab.deallocate(ptr);
+        }
+        assert!(slab.decommit());
+        assert!(slab.is_empty());
+
+        let mut again: Vec<_> = (0..capacity).map(|_| slab.allocate().unwrap()).collect();
+        assert!(slab.allocate().is_none());
+        again.sort();
+        again.dedup();
+        assert_eq!(again.len(), capacity);
+        for ptr in again {
+            slab.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "std", feature = "fresh_zeroed"))]
+    fn test_decommit_forgets_touched_slots() {
+        let mut slab = Slab::new_for_min_capacity(64, 256).unwrap();
+        let ptrs: Vec<_> = (0..slab.stats().capacity).map(|_| slab.allocate().unwrap()).collect();
+        for &ptr in &ptrs {
+            slab.deallocate(ptr);
+        }
+        assert!((0..slab.capacity).all(|index| slab.touched.get(index)));
+
+        assert!(slab.decommit());
+        assert!((0..slab.capacity).all(|index| !slab.touched.get(index)));
+        let ptr = slab.allocate_fresh_or_zeroed().unwrap();
+        assert!(unsafe { slice::from_raw_parts(ptr.as_ptr(), 64) }.iter().all(|&b| b == 0));
+        slab.deallocate(ptr);
+    }
+
+    #[test]
+    #[cfg(feature = "huge_pages")]
+    fn test_new_huge() {
+        assert!(Slab::new_huge(64, 0).is_none());
+        assert!(Slab::new_huge(0, 1).is_none());
+
+        let mut slab = Slab::new_huge(64, 1).unwrap();
+        assert_eq!(slab.stats().capacity, HUGE_PAGE_SIZE / 64);
+
+        let ptrs: Vec<_> = (0..HUGE_PAGE_SIZE / 64).map(|_| slab.allocate().unwrap()).collect();
+        assert!(slab.is_full());
+        assert!(ptrs.iter().all(|&ptr| slab.contains(ptr)));
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+    }
+
+    /// Runs `f` in a forked child and reports whether `SIGSEGV` killed it.
+    #[cfg(all(target_os = "linux", feature = "guard_pages"))]
+    fn segfaults(f: impl FnOnce()) -> bool {
+        extern "C" {
+            fn fork() -> i32;
+            fn waitpid(pid: i32, status: *mut i32, options: i32) -> i32;
+            fn _exit(status: i32) -> !;
+        }
+        let pid = unsafe { fork() };
+        assert!(pid >= 0, "fork failed");
+        if pid == 0 {
+            f();
+            unsafe { _exit(0) };
+        }
+        let mut status = 0;
+        assert_eq!(unsafe { waitpid(pid, &mut status, 0) }, pid);
+        status & 0x7f == 11
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "guard_pages"))]
+    fn test_guard_pages() {
+        let mut slab = Slab::new_with_guard_pages(64).unwrap();
+        let ptrs: Vec<_> = (0..slab.stats().capacity).map(|_| slab.allocate().unwrap()).collect();
+        for &ptr in &ptrs {
+            unsafe { ptr.as_ptr().write_bytes(0xA5, 64) };
+        }
+        let last = *ptrs.last().unwrap();
+        let first = ptrs[0];
+
+        assert!(segfaults(|| unsafe { last.as_ptr().add(64).write_volatile(0) }));
+        assert!(segfaults(|| unsafe { first.as_ptr().sub(1).write_volatile(0) }));
+        assert!(!segfaults(|| unsafe { last.as_ptr().add(63).write_volatile(0) }));
+
+        for ptr in ptrs {
+            slab.deallocate(ptr);
+        }
+        let split = slab.split_at(32).unwrap();
+        drop(slab);
+        drop(split);
+    }
+
+    #[test]
+    fn test_allocate_from() {
+        let mut allocator = SlabAllocator::new(64);
+        let capacity = Slab::capacity_for(64);
+        assert!(allocator.allocate_from(MAX_SLABS).is_none());
+
+        let ptrs: Vec<_> = (0..capacity).map(|_| allocator.allocate_from(3).unwrap()).collect();
+        assert!(allocator.allocate_from(3).is_none());
+        assert_eq!(allocator.slab_count(), 1);
+        assert!(allocator.slabs[3].as_ref().unwrap().is_full());
+        assert!(ptrs.iter().all(|&ptr| allocator.slabs[3].as_ref().unwrap().contains(ptr)));
+
+        let other = allocator.allocate_from(0).unwrap();
+        assert!(allocator.slabs[0].as_ref().unwrap().contains(other));
+        assert_eq!(allocator.allocated(), capacity + 1);
+        assert_eq!(allocator.counters().alloc_misses, 2);
+        for ptr in ptrs.into_iter().chain([other]) {
+            allocator.deallocate(ptr);
+        }
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let capacity = Slab::capacity_for(64);
+        let mut target = SlabAllocator::new_with_owner(64, 1);
+        let mut source = SlabAllocator::new_with_owner(64, 2);
+        let kept: Vec<_> = (0..capacity * 4).map(|_| target.allocate().unwrap()).collect();
+        let moved: Vec<_> = (0..capacity * 4).map(|_| source.allocate().unwrap()).collect();
+
+        assert_eq!(target.merge_from(&mut source), Ok(4));
+        assert_eq!(source.slab_count(), 0);
+        assert_eq!(source.allocated(), 0);
+        assert_eq!(target.slab_count(), 8);
+        assert_eq!(target.allocated(), capacity * 8);
+        assert!(target.iter().all(|slab| slab.owner() == 1));
+        for ptr in kept.into_iter().chain(moved) {
+            target.deallocate(ptr);
+        }
+        assert_eq!(target.allocated(), 0);
+
+        let mut overflow = SlabAllocator::new(64);
+        overflow.try_reserve(capacity * 10).unwrap();
+        assert_eq!(target.merge_from(&mut overflow), Err(SlabError::PartialMerge { moved: 8 }));
+        assert_eq!(overflow.slab_count(), 2);
+        assert!(target.is_exhausted());
+
+        assert_eq!(target.merge_from(&mut SlabAllocator::new(128)), Err(SlabError::ObjectSizeMismatch));
+    }
+
+    #[test]
+    fn test_replay_log() {
+        let capacity = Slab::capacity_for(128);
+        let mut recorded = SlabAllocator::new(128);
+        recorded.attach_log(LogRing::new(4 * capacity));
+        let mut ptrs: Vec<_> = (0..capacity + 3).map(|_| recorded.allocate().unwrap()).collect();
+        for ptr in ptrs.drain(..).step_by(3) {
+            recorded.deallocate(ptr);
+        }
+        recorded.allocate_from(2).unwrap();
+        recorded.allocate().unwrap();
+        let log = recorded.detach_log().unwrap();
+        assert!(recorded.log().is_none());
+        assert_eq!(log.overwritten(), 0);
+        assert_eq!(log.iter().next(), Some(&Op::Allocate { slab: 0, slot: 0 }));
+        assert_eq!(log.iter().nth(capacity + 4), Some(&Op::Deallocate { slab: 0, slot: 3 }));
+
+        let mut replayed = SlabAllocator::new(128);
+        assert_eq!(replayed.replay(log.iter()), Ok(()));
+        assert_eq!(replayed.collect_allocated().len(), recorded.allocated());
+        let slots = |allocator: &SlabAllocator| {
+            let mut slots: Vec<_> = allocator.collect_allocated().into_iter().map(|ptr| allocator.locate(ptr)).collect();
+            slots.sort();
+            slots
+        };
+        assert_eq!(slots(&replayed), slots(&recorded));
+
+        let diverging = [Op::Allocate { slab: 0, slot: 0 }, Op::Deallocate { slab: 0, slot: 1 }];
+        let mut fresh = SlabAllocator::new(128);
+        assert_eq!(fresh.replay(&diverging), Err(1));
+        fresh.clear();
+        assert_eq!(fresh.replay(&diverging[..1]), Ok(()));
+        for allocator in [&mut recorded, &mut replayed, &mut fresh] {
+            allocator.clear();
+        }
+
+        let mut strict = SlabAllocator::new(128);
+        strict.attach_log(LogRing::new(8));
+        let ptr = strict.allocate().unwrap();
+        strict.deallocate(ptr);
+        strict.deallocate(ptr);
+        let ops: Vec<_> = strict.log().unwrap().iter().copied().collect();
+        assert_eq!(ops, [Op::Allocate { slab: 0, slot: 0 }, Op::Deallocate { slab: 0, slot: 0 }]);
+        assert_eq!(strict.allocated(), 0);
+        assert!(strict.validate());
+
+        let mut ring = LogRing::new(2);
+        for slot in 0..5 {
+            ring.push(Op::Allocate { slab: 0, slot });
+        }
+        assert_eq!((ring.len(), ring.capacity(), ring.overwritten()), (2, 2, 3));
+        let kept: Vec<_> = ring.iter().copied().collect();
+        assert_eq!(kept, [Op::Allocate { slab: 0, slot: 3 }, Op::Allocate { slab: 0, slot: 4 }]);
+        let mut empty = LogRing::new(0);
+        empty.push(Op::Allocate { slab: 0, slot: 0 });
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_replay_compaction_and_clear() {
+        let capacity = Slab::capacity_for(256);
+        let mut recorded = SlabAllocator::new(256);
+        recorded.attach_log(LogRing::new(16 * capacity));
+        let ptrs = recorded.allocate_n(capacity * 3).unwrap();
+        #[cfg(feature = "tags")]
+        recorded.allocate_tagged(7).unwrap();
+        for (index, &ptr) in ptrs.iter().enumerate() {
+            if index >= capacity - 2 && !index.is_multiple_of(5) {
+                recorded.deallocate(ptr);
+            }
+        }
+        let report = recorded.compact_with(|_, _| {});
+        assert!(report.objects_moved > 0 && report.slabs_freed > 0);
+        let log = recorded.log().unwrap();
+        assert!(log.iter().any(|op| matches!(op, Op::Release { .. })));
+
+        let slots = |allocator: &SlabAllocator| {
+            let mut slots: Vec<_> = allocator.collect_allocated().into_iter().map(|ptr| allocator.locate(ptr)).collect();
+            slots.sort();
+            slots
+        };
+        let mut replayed = SlabAllocator::new(256);
+        assert_eq!(replayed.replay(log.iter()), Ok(()));
+        assert_eq!(replayed.slab_count(), recorded.slab_count());
+        assert_eq!(slots(&replayed), slots(&recorded));
+
+        recorded.clear();
+        recorded.allocate().unwrap();
+        let log = recorded.detach_log().unwrap();
+        replayed.clear();
+        replayed = SlabAllocator::new(256);
+        assert_eq!(replayed.replay(log.iter()), Ok(()));
+        assert_eq!(slots(&replayed), slots(&recorded));
+        assert_eq!(replayed.allocated(), 1);
+
+        let mut fresh = SlabAllocator::new(256);
+        fresh.allocate().unwrap();
+        assert_eq!(fresh.replay(&[Op::Release { slab: 0 }]), Err(0));
+        for allocator in [&mut recorded, &mut replayed, &mut fresh] {
+            allocator.clear();
+        }
+    }
+
+    #[test]
+    fn test_compact_with() {
+        let mut allocator = SlabAllocator::new(256);
+        let capacity = Slab::capacity_for(256);
+        let mut ptrs = Vec::new();
+        let mut markers = Vec::new();
+        for index in 0..capacity * 3 {
+            let ptr = allocator.allocate().unwrap();
+            if index < capacity - 2 || index.is_multiple_of(5) {
+                unsafe { ptr.cast::<usize>().as_ptr().write(index) };
+                ptrs.push(ptr);
+                markers.push(index);
+            }
+        }
+        for ptr in allocator.collect_allocated() {
+            if !ptrs.contains(&ptr) {
+                allocator.deallocate(ptr);
+            }
+        }
+        let densest = allocator.iter().max_by_key(|slab| slab.allocated).unwrap().memory;
+
+        let mut remaps = Vec::new();
+        let report = allocator.compact_with(|old, new| {
+            assert_eq!(unsafe { new.cast::<usize>().as_ptr().read() }, unsafe { old.cast::<usize>().as_ptr().read() });
+            remaps.push((old, new));
+        });
+        assert_eq!(report.objects_moved, remaps.len());
+        assert_eq!(report.slabs_freed, 1);
+        assert_eq!(report.bytes_reclaimed, SLAB_SIZE);
+        assert_eq!(allocator.slab_count(), 2);
+        assert!(allocator.iter().any(|slab| slab.memory == densest && slab.is_full()));
+        assert!(allocator.validate());
+
+        for ptr in &mut ptrs {
+            if let Some(&(_, new)) = remaps.iter().find(|&&(old, _)| old == *ptr) {
+                *ptr = new;
+            }
+        }
+        let mut read: Vec<_> = ptrs.iter().map(|ptr| unsafe { ptr.cast::<usize>().as_ptr().read() }).collect();
+        read.sort_unstable();
+        assert_eq!(read, markers);
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+        assert_eq!(allocator.allocated(), 0);
+    }
+
+    #[test]
+    fn test_try_reclaim_to() {
+        let capacity = Slab::capacity_for(64);
+        let mut source = SlabAllocator::new_with_owner(64, 1);
+        let mut target = SlabAllocator::new_with_owner(64, 2);
+        let ptrs: Vec<_> = (0..capacity * 3).map(|_| source.allocate().unwrap()).collect();
+        for &ptr in &ptrs[capacity..] {
+            source.deallocate(ptr);
+        }
+        let spare: Vec<_> = source.iter_empty().map(|slab| slab.memory).collect();
+
+        assert_eq!(source.try_reclaim_to(&mut SlabAllocator::new(128)), 0);
+        assert_eq!(source.try_reclaim_to(&mut target), 2);
+        assert_eq!(source.slab_count(), 1);
+        assert_eq!(target.slab_count(), 2);
+        assert!(target.iter().all(|slab| slab.owner() == 2 && spare.contains(&slab.memory)));
+
+        let reused: Vec<_> = (0..capacity * 2).map(|_| target.allocate().unwrap()).collect();
+        assert_eq!(target.slab_count(), 2);
+        for ptr in reused {
+            target.deallocate(ptr);
+        }
+        for &ptr in &ptrs[..capacity] {
+            source.deallocate(ptr);
+        }
+
+        let mut full = SlabAllocator::new(64);
+        full.try_reserve(capacity * MAX_SLABS).unwrap();
+        assert_eq!(target.try_reclaim_to(&mut full), 0);
+        assert_eq!(target.slab_count(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let large = Layout::from_size_align(512, 8).unwrap();
+        let mut cache = SlabCache::new();
+        assert!(cache.validate());
+
+        let ptrs: Vec<_> = (0..100).map(|_| cache.allocate(small).unwrap()).collect();
+        let big = cache.allocate(large).unwrap();
+        for &ptr in ptrs.iter().step_by(3) {
+            cache.deallocate(ptr, small);
+        }
+        assert!(cache.validate());
+
+        cache.large.allocated += 1;
+        assert!(!cache.validate());
+        cache.large.allocated -= 1;
+
+        let slab = cache.small.slabs[0].as_ref().unwrap();
+        let head = slab.free_list.unwrap();
+        let next = slab.next_of(head);
+        slab.set_next(head, Some(head));
+        assert!(!slab.validate());
+        assert!(!cache.validate());
+        cache.small.slabs[0].as_ref().unwrap().set_next(head, next);
+        assert!(cache.validate());
+
+        cache.deallocate(big, large);
+        cache.clear();
+    }
+
+    #[test]
+    fn test_is_consistent() {
+        let mut slab = Slab::new(64).unwrap();
+        assert_eq!(slab.free_list_depth(), slab.capacity);
+        let ptrs: Vec<_> = (0..10).map(|_| slab.allocate().unwrap()).collect();
+        slab.deallocate(ptrs[4]);
+        assert_eq!(slab.free_list_depth(), slab.capacity - 9);
+        assert!(slab.is_consistent());
+
+        slab.allocated += 1;
+        assert!(!slab.is_consistent());
+        slab.allocated = slab.capacity + 1;
+        assert!(!slab.is_consistent());
+        slab.allocated = 9;
+
+        let head = slab.free_list.unwrap();
+        let next = slab.next_of(head);
+        slab.set_next(head, Some(head));
+        assert_eq!(slab.free_list_depth(), slab.capacity + 1);
+        assert!(!slab.is_consistent());
+        slab.set_next(head, next);
+        assert!(slab.is_consistent());
+
+        for (index, &ptr) in ptrs.iter().enumerate() {
+            if index != 4 {
                 slab.deallocate(ptr);
-                return;
             }
         }
+        assert!(slab.is_consistent());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "slab dropped with 2 live objects")]
+    fn test_drop_with_live_objects_panics() {
+        let mut slab = Slab::new(64).unwrap();
+        slab.allocate().unwrap();
+        slab.allocate().unwrap();
+        drop(slab);
     }
-}
 
-pub struct SlabCache {
-    small: SlabAllocator,
-    medium: SlabAllocator,
-    large: SlabAllocator,
-}
+    #[test]
+    fn test_drop_after_into_raw_parts_or_reset() {
+        let mut slab = Slab::new(64).unwrap();
+        let ptr = slab.allocate().unwrap();
+        let (memory, size, object_size, requested_size, allocated, free_list) = slab.into_raw_parts();
+        let mut slab = unsafe { Slab::from_raw_parts(memory, size, object_size, requested_size, allocated, free_list) };
+        assert!(slab.is_allocated(ptr));
+        slab.reset();
+        drop(slab);
 
-impl SlabCache {
-    pub const fn new() -> Self {
-        SlabCache {
-            small: SlabAllocator::new(64),
-            medium: SlabAllocator::new(256),
-            large: SlabAllocator::new(512),
-        }
+        let mut borrowed = [0u64; 64];
+        let memory = NonNull::new(borrowed.as_mut_ptr().cast::<u8>()).unwrap();
+        let mut slab = unsafe { Slab::new_from_memory(memory, 512, 64) }.unwrap();
+        slab.allocate().unwrap();
+        drop(slab);
     }
 
-    pub fn allocate(&mut self, layout: Layout) -> Option<NonNull<u8>> {
-        let size = layout.size();
-        
-        if size <= 64 {
-            self.small.allocate()
-        } else if size <= 256 {
-            self.medium.allocate()
-        } else if size <= 512 {
-            self.large.allocate()
-        } else {
-            None
+    #[test]
+    fn test_byte_totals() {
+        let mut allocator = SlabAllocator::new(200);
+        let ptrs: Vec<_> = (0..30).map(|_| allocator.allocate().unwrap()).collect();
+        for &ptr in ptrs.iter().step_by(4) {
+            allocator.deallocate(ptr);
         }
-    }
 
-    pub fn deallocate(&mut self, ptr: NonNull<u8>, layout: Layout) {
-        let size = layout.size();
-        
-        if size <= 64 {
-            self.small.deallocate(ptr);
-        } else if size <= 256 {
-            self.medium.deallocate(ptr);
-        } else if size <= 512 {
-            self.large.deallocate(ptr);
+        for slab in allocator.iter() {
+            assert_eq!(slab.total_allocation_bytes() + slab.free_bytes(), slab.total_capacity_bytes());
+            let stats = slab.stats();
+            assert_eq!(stats.bytes_allocated, stats.allocated * stats.object_size);
+            assert_eq!(stats.bytes_capacity, slab.total_capacity_bytes());
         }
+        let wasted: usize = allocator.iter().map(Slab::wasted_bytes).sum();
+        assert!(wasted > 0);
+        assert_eq!(allocator.total_capacity_bytes() + wasted, SLAB_SIZE * allocator.slab_count());
+        #[cfg(not(feature = "align16"))]
+        assert_eq!(allocator.total_allocation_bytes(), allocator.allocated() * 200);
+        allocator.clear();
+
+        let mut cache = SlabCache::new();
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let ptr = cache.allocate(layout).unwrap();
+        assert_eq!(cache.total_allocation_bytes(), 256);
+        assert_eq!(cache.total_capacity_bytes(), SLAB_SIZE);
+        cache.deallocate(ptr, layout);
     }
-}
 
-pub struct GlobalSlabAllocator;
+    #[test]
+    fn test_with_initial_slabs() {
+        let allocator = SlabAllocator::with_initial_slabs(128, 5).unwrap();
+        assert_eq!(allocator.slab_count(), 5);
+        assert_eq!(allocator.count_empty_slabs(), 5);
 
-unsafe impl GlobalAlloc for GlobalSlabAllocator {
-    /// # Safety
-    /// Caller must ensure the layout is valid and non-zero sized.
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        alloc(layout)
-    }
+        let capped = SlabAllocator::with_initial_slabs(128, MAX_SLABS + 4).unwrap();
+        assert_eq!(capped.count_empty_slabs(), MAX_SLABS);
 
-    /// # Safety
-    /// Pointer must have been allocated with the same layout via alloc.
-    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        dealloc(ptr, layout);
+        assert!(SlabAllocator::with_initial_slabs(0, 1).is_none());
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(true));
+        let starved = SlabAllocator::with_initial_slabs(128, 2);
+        FAIL_BACKING_ALLOC.with(|fail| fail.set(false));
+        assert!(starved.is_none());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_clone_is_independent() {
+        let mut original = SlabAllocator::new(64);
+        let ptrs: Vec<_> = (0..100).map(|_| original.allocate().unwrap()).collect();
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            unsafe { ptr.as_ptr().write(i as u8) };
+        }
+        original.deallocate(ptrs[1]);
 
-    extern crate std;
-    use std::vec::Vec;
+        let mut copy = original.clone();
+        assert!(copy.validate());
+        assert_eq!(copy.allocated(), original.allocated());
+        assert_eq!(copy.preferred_numa_node(), original.preferred_numa_node());
+        assert!(ptrs.iter().all(|&ptr| !copy.contains(ptr)));
 
-    #[test]
-    fn test_slab_creation() {
-        let slab = Slab::new(64);
-        assert!(slab.is_some());
-        let slab = slab.unwrap();
-        assert_eq!(slab.object_size, 64);
-        assert!(slab.capacity > 0);
-        assert!(slab.is_empty());
+        let copied: Vec<_> = copy.iter().flat_map(Slab::allocated_addresses).collect();
+        assert_eq!(copied.len(), 99);
+        for &ptr in &copied {
+            unsafe { ptr.as_ptr().write(0xFF) };
+        }
+        assert!(ptrs.iter().enumerate().filter(|&(i, _)| i != 1).all(|(i, ptr)| unsafe { *ptr.as_ptr() } == i as u8));
+
+        let fresh = copy.allocate().unwrap();
+        assert!(copy.contains(fresh));
+        assert!(!original.contains(fresh));
+        assert_eq!(original.allocate(), Some(ptrs[1]));
+        original.clear();
+        copy.clear();
     }
 
     #[test]
-    fn test_slab_allocate_deallocate() {
-        let mut slab = Slab::new(64).unwrap();
-        let ptr = slab.allocate();
-        assert!(ptr.is_some());
-        assert!(!slab.is_empty());
-        
-        let ptr = ptr.unwrap();
-        slab.deallocate(ptr);
-        assert!(slab.is_empty());
+    fn test_internal_fragmentation() {
+        let cache = SlabCache::new();
+        let waste = |size| cache.internal_fragmentation(Layout::from_size_align(size, 1).unwrap());
+        assert_eq!(waste(64), 0);
+        assert_eq!(waste(65), 191);
+        assert_eq!(waste(256), 0);
+        assert_eq!(waste(257), 255);
+        assert_eq!(waste(1), 7);
+        assert_eq!(waste(9), 55);
+        assert_eq!(waste(512), 0);
+        assert_eq!(waste(513), 0);
     }
 
     #[test]
-    fn test_slab_multiple_allocations() {
-        let mut slab = Slab::new(64).unwrap();
-        let mut ptrs = Vec::new();
+    fn test_new_for_type() {
+        let mut cache = SlabCache::new_for_type::<u128>();
+        assert_eq!(cache.small.object_size(), 16);
+        assert_eq!(cache.medium.object_size(), 64);
+        assert_eq!(cache.large.object_size(), 256);
 
-        for _ in 0..10 {
-            if let Some(ptr) = slab.allocate() {
-                ptrs.push(ptr);
-            }
-        }
+        let layout = Layout::new::<u128>();
+        let ptr = cache.allocate(layout).unwrap();
+        assert!(cache.small.contains(ptr));
+        assert_eq!(cache.internal_fragmentation(layout), 0);
+        assert_eq!(cache.internal_fragmentation(Layout::from_size_align(100, 8).unwrap()), 156);
+        assert!(cache.allocate(Layout::from_size_align(300, 8).unwrap()).is_none());
+        cache.deallocate(ptr, layout);
 
-        assert_eq!(ptrs.len(), 10);
-        assert_eq!(slab.allocated, 10);
+        let capped = SlabCache::new_for_type::<[u64; 8]>();
+        assert_eq!(capped.medium.object_size(), 256);
+        assert_eq!(capped.large.object_size(), MAX_OBJECT_SIZE);
+    }
 
-        for ptr in ptrs {
+    #[test]
+    fn test_memory_pressure_score() {
+        let mut slab = Slab::new(512).unwrap();
+        assert_eq!(slab.memory_pressure_score(), 0);
+        let ptrs: Vec<_> = (0..4).map(|_| slab.allocate().unwrap()).collect();
+        assert_eq!(slab.memory_pressure_score(), 127);
+        let rest: Vec<_> = (0..4).map(|_| slab.allocate().unwrap()).collect();
+        assert_eq!(slab.memory_pressure_score(), 255);
+        for ptr in ptrs.into_iter().chain(rest) {
             slab.deallocate(ptr);
         }
 
-        assert!(slab.is_empty());
+        let mut allocator = SlabAllocator::new(512);
+        assert_eq!(allocator.memory_pressure_score(), 0);
+        for _ in 0..10 {
+            allocator.allocate().unwrap();
+        }
+        assert_eq!(allocator.memory_pressure_score(), 255);
+        allocator.clear();
     }
 
     #[test]
-    fn test_slab_full() {
-        let mut slab = Slab::new(64).unwrap();
-        let capacity = slab.capacity;
-        let mut ptrs = Vec::new();
-
-        for _ in 0..capacity {
-            if let Some(ptr) = slab.allocate() {
-                ptrs.push(ptr);
-            }
-        }
+    fn test_allocate_n() {
+        let mut allocator = SlabAllocator::new(512);
+        let total = allocator.max_capacity();
+        assert_eq!(total, MAX_SLABS * SLAB_SIZE / 512);
+        assert_eq!(SlabAllocator::new(0).max_capacity(), 0);
+        let batch = allocator.allocate_n(10).unwrap();
+        assert_eq!(batch.len(), 10);
 
-        assert!(slab.is_full());
-        assert!(slab.allocate().is_none());
+        let filler: Vec<_> = (0..total - 13).map(|_| allocator.allocate().unwrap()).collect();
+        assert!(allocator.can_allocate(3));
+        assert!(!allocator.can_allocate(5));
 
-        slab.deallocate(ptrs[0]);
-        assert!(!slab.is_full());
-    }
+        let before = allocator.per_slab_stats().collect::<Vec<_>>();
+        assert!(allocator.allocate_n(5).is_none());
+        assert_eq!(allocator.allocated(), total - 3);
+        assert_eq!(allocator.per_slab_stats().collect::<Vec<_>>(), before);
+        let rest = allocator.allocate_n(3).unwrap();
+        assert_eq!(rest.len(), 3);
+        assert!(allocator.allocate().is_none());
 
-    #[test]
-    fn test_slab_contains() {
-        let mut slab = Slab::new(64).unwrap();
-        let ptr = slab.allocate().unwrap();
-        assert!(slab.contains(ptr));
-        
-        let external = NonNull::new(0x1000 as *mut u8).unwrap();
-        assert!(!slab.contains(external));
+        for ptr in batch.into_iter().chain(filler).chain(rest) {
+            allocator.deallocate(ptr);
+        }
     }
 
     #[test]
-    fn test_allocator_basic() {
+    fn test_reset_stats() {
         let mut allocator = SlabAllocator::new(64);
-        let ptr = allocator.allocate();
-        assert!(ptr.is_some());
-        
-        let ptr = ptr.unwrap();
-        allocator.deallocate(ptr);
+        let ptrs: Vec<_> = (0..10).map(|_| allocator.allocate().unwrap()).collect();
+        for &ptr in &ptrs[4..] {
+            allocator.deallocate(ptr);
+        }
+        assert_eq!(allocator.peak_allocated(), 10);
+
+        allocator.reset_stats();
+        assert_eq!(allocator.counters(), AllocCounters::default());
+        assert_eq!(allocator.allocated(), 4);
+        assert_eq!(allocator.peak_allocated(), 10);
+
+        allocator.allocate().unwrap();
+        assert_eq!(allocator.counters().alloc_calls, 1);
+        assert_eq!(allocator.counters().alloc_hits, 1);
+        assert_eq!(allocator.peak_allocated(), 10);
+        allocator.clear();
     }
 
     #[test]
-    fn test_allocator_multiple_slabs() {
+    fn test_per_slab_stats() {
         let mut allocator = SlabAllocator::new(64);
-        let mut ptrs = Vec::new();
+        assert_eq!(allocator.per_slab_stats().count(), 0);
 
-        for _ in 0..200 {
-            if let Some(ptr) = allocator.allocate() {
-                ptrs.push(ptr);
-            }
+        let capacity = Slab::capacity_for(64);
+        let mut ptrs = Vec::new();
+        for _ in 0..capacity + 3 {
+            ptrs.push(allocator.allocate().unwrap());
         }
 
-        assert!(ptrs.len() >= 100);
+        let stats: Vec<SlabStats> = allocator.per_slab_stats().collect();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0],
+            SlabStats {
+                object_size: 64,
+                requested_size: 64,
+                capacity,
+                allocated: capacity,
+                free_count: 0,
+                bytes_allocated: capacity * 64,
+                bytes_capacity: capacity * 64,
+            }
+        );
+        assert_eq!(stats[1].allocated, 3);
+        assert_eq!(stats[1].free_count, capacity - 3);
 
         for ptr in ptrs {
             allocator.deallocate(ptr);
@@ -380,14 +6260,87 @@ mod tests {
         cache.deallocate(ptr, layout);
     }
 
+    #[test]
+    fn test_slab_arena() {
+        let arena = SlabArena::new();
+        let x = arena.alloc_val(42u32);
+        let pair = arena.alloc_val((1u64, [7u8; 100]));
+        let page = arena.alloc_val([0u8; 600]);
+        let unit = arena.alloc_val(());
+        *x += 1;
+        pair.1[99] = 8;
+        page[599] = 9;
+        assert_eq!((*x, pair.0, pair.1[99], page[599], *unit), (43, 1, 8, 9, ()));
+        assert_eq!(arena.allocated(), 3);
+
+        #[repr(align(64))]
+        struct Aligned(#[allow(dead_code)] u8);
+        assert!(arena.try_alloc_val(Aligned(0)).is_err());
+
+        let limited = SlabArena::with_cache(SlabCache::with_memory_limit(SLAB_SIZE));
+        assert!(limited.try_alloc_val(1u8).is_ok());
+        assert_eq!(limited.try_alloc_val([1u8; 64]).err(), Some([1u8; 64]));
+    }
+
+    #[test]
+    #[cfg(feature = "offset_free_list")]
+    fn test_slab_arena_offset_free_list_alignment() {
+        #[repr(align(16))]
+        #[derive(Debug, PartialEq)]
+        struct Vector([u32; 4]);
+
+        let arena = SlabArena::new();
+        assert_eq!(arena.try_alloc_val(Vector([1; 4])).err(), Some(Vector([1; 4])));
+        let odd = SlabArena::with_cache(SlabCache::new_for_size_range(6, 54, 3).unwrap());
+        let results: Vec<_> = (0..8).map(|_| odd.try_alloc_val(0u64).map(|value| value as *mut u64)).collect();
+        assert!(results.iter().flatten().all(|value| value.is_aligned()));
+        assert!(results.iter().any(Result::is_err));
+        assert_eq!(odd.allocated(), results.iter().flatten().count());
+        assert_eq!(arena.allocated(), 0);
+    }
+
     #[test]
     fn test_cache_oversized() {
         let mut cache = SlabCache::new();
-        let layout = Layout::from_size_align(1024, 8).unwrap();
+        let layout = Layout::from_size_align(SLAB_SIZE + 1, 8).unwrap();
         let ptr = cache.allocate(layout);
         assert!(ptr.is_none());
     }
 
+    #[test]
+    fn test_cache_oversize_slab() {
+        let mut cache = SlabCache::new();
+        let layout = Layout::from_size_align(600, 8).unwrap();
+        let ptr = cache.allocate(layout).unwrap();
+        unsafe { ptr::write_bytes(ptr.as_ptr(), 0xAB, 600) };
+        assert!(cache.contains(ptr));
+        assert_eq!(cache.allocated(), 1);
+        assert_eq!(cache.oversize_count(), 1);
+        assert_eq!(cache.memory_reserved(), SLAB_SIZE);
+
+        let page = cache.allocate(Layout::from_size_align(SLAB_SIZE, SLAB_SIZE).unwrap()).unwrap();
+        assert_eq!(page.as_ptr() as usize % SLAB_SIZE, 0);
+        assert!(cache.allocate(Layout::from_size_align(600, 2 * SLAB_SIZE).unwrap()).is_none());
+
+        cache.deallocate(ptr, layout);
+        cache.deallocate(page, Layout::from_size_align(SLAB_SIZE, SLAB_SIZE).unwrap());
+        assert!(!cache.contains(ptr));
+        assert_eq!(cache.allocated(), 0);
+        assert_eq!(cache.memory_reserved(), 0);
+
+        let ptrs: Vec<_> = (0..MAX_SLABS).map(|_| cache.allocate(layout).unwrap()).collect();
+        assert!(cache.allocate(layout).is_none());
+        cache.clear();
+        assert_eq!(cache.oversize_count(), 0);
+        assert!(!ptrs.iter().any(|&ptr| cache.contains(ptr)));
+
+        let mut limited = SlabCache::with_memory_limit(SLAB_SIZE);
+        let ptr = limited.allocate(layout).unwrap();
+        assert!(limited.allocate(layout).is_none());
+        limited.deallocate(ptr, layout);
+        assert!(OversizeSlab::new(MAX_OBJECT_SIZE).is_none());
+    }
+
     #[test]
     fn test_zero_size() {
         let slab = Slab::new(0);
@@ -406,6 +6359,47 @@ mod tests {
         let ptr = slab.allocate().unwrap();
         let addr = ptr.as_ptr() as usize;
         assert_eq!(addr % 8, 0);
+        slab.deallocate(ptr);
+    }
+
+    #[test]
+    fn test_cache_classes_are_16_byte_aligned() {
+        let mut cache = SlabCache::new();
+        let capacity = Slab::capacity_for(SMALL_OBJECT_SIZE);
+        for size in [17, 48, SMALL_OBJECT_SIZE, 100, MEDIUM_OBJECT_SIZE, 300, LARGE_OBJECT_SIZE] {
+            let layout = Layout::from_size_align(size, 16).unwrap();
+            // Past the first slab, so coloured slabs are covered too.
+            let ptrs = cache.allocate_many(layout, capacity + 1).unwrap();
+            assert!(ptrs.iter().all(|ptr| (ptr.as_ptr() as usize).is_multiple_of(16)), "size {size}");
+            for ptr in ptrs {
+                cache.deallocate(ptr, layout);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "align16", not(feature = "offset_free_list")))]
+    fn test_align16_slots() {
+        assert_eq!(Slab::slot_align(), 16);
+        assert_eq!(Slab::align_size(17), 32);
+        #[cfg(not(feature = "strict_size"))]
+        assert_eq!(Slab::new(4).unwrap().object_size_class(), Some(SizeClass::Small));
+        assert_eq!(Slab::capacity_for(TINY_OBJECT_SIZE), 4 * Slab::capacity_for(SMALL_OBJECT_SIZE));
+
+        let mut cache = SlabCache::new();
+        let layout = Layout::from_size_align(4, 16).unwrap();
+        let ptrs = cache.allocate_many(layout, 300).unwrap();
+        assert!(ptrs.iter().all(|ptr| (ptr.as_ptr() as usize).is_multiple_of(16)));
+        for ptr in ptrs {
+            cache.deallocate(ptr, layout);
+        }
+        assert!(Slab::from_layout(Layout::from_size_align(32, 16).unwrap()).is_some());
+        assert!(GlobalSlabAllocator::serves(Layout::from_size_align(32, 16).unwrap()));
+
+        assert!(Slab::new_with_color(100, 40).is_none());
+        assert_eq!(Slab::new_with_color(100, 48).unwrap().stats().capacity, (SLAB_SIZE - 48) / Slab::align_size(100));
+        // 160-byte slots leave 96 spare bytes: colours 0, 64, 32, 0, ...
+        assert_allocator_colors(160, [0, 64, 32]);
     }
 
     #[test]
@@ -420,5 +6414,346 @@ mod tests {
         let addr2 = ptr2.as_ptr() as usize;
         
         assert_eq!(addr1, addr2);
+        slab.deallocate(ptr2);
+    }
+
+    #[test]
+    fn test_global_zero_size_layout() {
+        let allocator = GlobalSlabAllocator::new();
+        let layout = Layout::from_size_align(0, 8).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(ptr as usize % layout.align(), 0);
+            allocator.dealloc(ptr, layout);
+        }
+    }
+
+    #[test]
+    fn test_global_lock_released_on_panic() {
+        let allocator = GlobalSlabAllocator::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            allocator.with_cache(|_| panic!("while holding the lock"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(allocator.leak_check(), 0);
+    }
+
+    #[test]
+    fn test_global_leak_check() {
+        let allocator = GlobalSlabAllocator::new();
+        let small = Layout::from_size_align(32, 8).unwrap();
+        let large = Layout::from_size_align(400, 8).unwrap();
+        let oversized = Layout::from_size_align(2048, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(small);
+            let b = allocator.alloc(large);
+            let c = allocator.alloc(oversized);
+            assert!(!a.is_null() && !b.is_null() && !c.is_null());
+            assert_eq!(allocator.leak_check(), 2);
+
+            allocator.dealloc(c, oversized);
+            allocator.dealloc(a, small);
+            #[cfg(feature = "thread_cache")]
+            allocator.flush_thread_cache();
+            assert_eq!(allocator.leak_check(), 1);
+
+            allocator.force_reset();
+            assert_eq!(allocator.leak_check(), 0);
+        }
+    }
+
+    fn exercise_locked<L: Lock>(allocator: &LockedSlabAllocator<L>) {
+        let small = Layout::from_size_align(32, 8).unwrap();
+        let oversized = Layout::from_size_align(MAX_OBJECT_SIZE + 1, 8).unwrap();
+        unsafe {
+            let ptrs = [allocator.alloc(small), allocator.alloc(small), allocator.alloc(oversized)];
+            assert!(ptrs.iter().all(|ptr| !ptr.is_null()));
+            assert_eq!(allocator.lock().allocated(), 2);
+            assert!(!allocator.lock().contains(NonNull::new(ptrs[2]).unwrap()));
+
+            allocator.dealloc(ptrs[0], small);
+            allocator.dealloc(ptrs[1], small);
+            allocator.dealloc(ptrs[2], oversized);
+            assert!(!allocator.alloc(Layout::new::<()>()).is_null());
+        }
+        assert_eq!(allocator.lock().allocated(), 0);
+    }
+
+    #[test]
+    fn test_locked_spin() {
+        static ALLOCATOR: LockedSlabAllocator<lock::SpinLock> =
+            LockedSlabAllocator::new(lock::SpinLock::new(SlabCache::new()));
+        exercise_locked(&ALLOCATOR);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_locked_std_mutex() {
+        static ALLOCATOR: LockedSlabAllocator<lock::StdMutexLock> =
+            LockedSlabAllocator::new(lock::StdMutexLock::new(SlabCache::new()));
+        exercise_locked(&ALLOCATOR);
+
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        unsafe { ALLOCATOR.dealloc(ALLOCATOR.alloc(layout), layout) };
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(ALLOCATOR.lock().allocated(), 0);
+    }
+
+    #[test]
+    fn test_global_class_stats() {
+        let allocator = GlobalSlabAllocator::new();
+        let small = Layout::from_size_align(32, 8).unwrap();
+        let medium = Layout::from_size_align(200, 8).unwrap();
+        let tiny = Layout::new::<u32>();
+        assert_eq!(allocator.class_stats(), [(8, 0), (64, 0), (256, 0), (512, 0)]);
+        unsafe {
+            let ptrs = [allocator.alloc(small), allocator.alloc(small), allocator.alloc(medium)];
+            let node = allocator.alloc(tiny);
+            assert_eq!(allocator.class_stats(), [(8, 1), (64, 2), (256, 1), (512, 0)]);
+
+            allocator.dealloc(ptrs[0], small);
+            allocator.dealloc(ptrs[1], small);
+            allocator.dealloc(ptrs[2], medium);
+            allocator.dealloc(node, tiny);
+            #[cfg(feature = "thread_cache")]
+            allocator.flush_thread_cache();
+        }
+        assert_eq!(allocator.class_stats(), [(8, 0), (64, 0), (256, 0), (512, 0)]);
+    }
+
+    #[test]
+    #[cfg(feature = "thread_cache")]
+    fn test_global_thread_cache() {
+        let allocator = GlobalSlabAllocator::new();
+        let layout = Layout::from_size_align(48, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(layout);
+            allocator.dealloc(a, layout);
+            assert_eq!(allocator.leak_check(), 1);
+            assert_eq!(allocator.alloc(layout), a);
+
+            let ptrs: Vec<_> = (0..thread_cache::SLOTS + 1).map(|_| allocator.alloc(layout)).collect();
+            allocator.dealloc(a, layout);
+            for &ptr in &ptrs {
+                allocator.dealloc(ptr, layout);
+            }
+            assert_eq!(allocator.leak_check(), 1);
+            allocator.flush_thread_cache();
+            assert_eq!(allocator.leak_check(), 0);
+        }
+
+        static SHARED: GlobalSlabAllocator = GlobalSlabAllocator::new();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| unsafe {
+                    for round in 0..1000 {
+                        let layout = Layout::from_size_align(16 + round % 400, 8).unwrap();
+                        let ptr = SHARED.alloc(layout);
+                        ptr.write_bytes(0x5A, layout.size());
+                        SHARED.dealloc(ptr, layout);
+                    }
+                    SHARED.flush_thread_cache();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(SHARED.leak_check(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "thread_cache")]
+    fn test_thread_cache_returned_on_thread_exit() {
+        static EXITING: GlobalSlabAllocator = GlobalSlabAllocator::new();
+        let layout = Layout::from_size_align(48, 8).unwrap();
+        std::thread::spawn(move || unsafe {
+            let ptrs: Vec<_> = (0..4).map(|_| EXITING.alloc(layout)).collect();
+            for ptr in ptrs {
+                EXITING.dealloc(ptr, layout);
+            }
+            assert_eq!(EXITING.leak_check(), 4);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(EXITING.leak_check(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "thread_cache")]
+    fn test_thread_cache_outlives_reset_allocator() {
+        use std::sync::mpsc;
+
+        let layout = Layout::from_size_align(48, 8).unwrap();
+        let reset = GlobalSlabAllocator::new();
+        let (parked_tx, parked_rx) = mpsc::channel();
+        let (reset_tx, reset_rx) = mpsc::channel();
+        let reset = &reset;
+        std::thread::scope(|scope| {
+            scope.spawn(move || unsafe {
+                let ptr = reset.alloc(layout);
+                reset.dealloc(ptr, layout);
+                parked_tx.send(()).unwrap();
+                reset_rx.recv().unwrap();
+
+                let next = GlobalSlabAllocator::new();
+                let ptr = next.alloc(layout);
+                next.dealloc(ptr, layout);
+                assert_eq!(next.leak_check(), 1);
+                assert_eq!(next.alloc(layout), ptr);
+                next.dealloc(ptr, layout);
+                next.flush_thread_cache();
+                assert_eq!(next.leak_check(), 0);
+            });
+            parked_rx.recv().unwrap();
+            reset.force_reset();
+            reset_tx.send(()).unwrap();
+        });
+        assert_eq!(reset.leak_check(), 0);
+    }
+
+    #[test]
+    fn test_fifo_hint_reuses_oldest_free_slot() {
+        let mut slab = Slab::new_with_hint(64, CacheHint::Fifo).unwrap();
+        let a = slab.allocate().unwrap();
+        let b = slab.allocate().unwrap();
+        let capacity = slab.capacity;
+        let mut rest = Vec::new();
+        for _ in 2..capacity {
+            rest.push(slab.allocate().unwrap());
+        }
+        assert!(slab.is_full());
+
+        slab.deallocate(a);
+        slab.deallocate(b);
+        assert_eq!(slab.allocate(), Some(a));
+        assert_eq!(slab.allocate(), Some(b));
+        slab.reset();
+
+        let mut lifo = Slab::new_with_hint(64, CacheHint::Lifo).unwrap();
+        let a = lifo.allocate().unwrap();
+        let b = lifo.allocate().unwrap();
+        lifo.deallocate(a);
+        lifo.deallocate(b);
+        assert_eq!(lifo.allocate(), Some(b));
+        assert_eq!(lifo.allocate(), Some(a));
+        lifo.reset();
+    }
+
+    #[test]
+    fn test_cache_allocate_with_hint() {
+        let mut cache = SlabCache::new();
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        let a = cache.allocate_with_hint(layout, CacheHint::Fifo).unwrap();
+        let b = cache.allocate_with_hint(layout, CacheHint::Fifo).unwrap();
+        cache.deallocate(a, layout);
+        cache.deallocate(b, layout);
+
+        let next = cache.allocate_with_hint(layout, CacheHint::Fifo).unwrap();
+        assert_ne!(next, a);
+        assert_ne!(next, b);
+        cache.deallocate(next, layout);
+    }
+
+    #[test]
+    fn test_allocate_with_hint_leaves_hint_unchanged() {
+        let mut slab = Slab::new(64).unwrap();
+        let a = slab.allocate().unwrap();
+        let b = slab.allocate().unwrap();
+        let c = slab.allocate_with_hint(CacheHint::Fifo).unwrap();
+        assert_eq!(slab.hint(), CacheHint::Default);
+        slab.deallocate(a);
+        slab.deallocate(b);
+        assert_eq!(slab.allocate(), Some(b));
+        assert_eq!(slab.allocate(), Some(a));
+        slab.deallocate(a);
+        slab.deallocate(b);
+        slab.deallocate(c);
+
+        let mut allocator = SlabAllocator::new(64);
+        let a = allocator.allocate().unwrap();
+        let b = allocator.allocate().unwrap();
+        let c = allocator.allocate_with_hint(CacheHint::Fifo).unwrap();
+        assert!(allocator.slabs.iter().flatten().all(|slab| slab.hint() == CacheHint::Default));
+        allocator.deallocate(a);
+        allocator.deallocate(b);
+        assert_eq!(allocator.allocate(), Some(b));
+        allocator.deallocate(b);
+        allocator.deallocate(c);
+    }
+}
+
+/// Runs under `cargo +nightly miri test`. Backing memory comes from
+/// `Box::into_raw` so Miri tracks every slot against a single allocation.
+#[cfg(all(test, miri))]
+mod miri_tests {
+    use super::*;
+    use alloc::boxed::Box;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    fn boxed_region() -> (NonNull<u8>, *mut [u64; 512]) {
+        let raw = Box::into_raw(Box::new([0u64; 512]));
+        (NonNull::new(raw.cast::<u8>()).unwrap(), raw)
+    }
+
+    #[test]
+    fn test_miri_slab_lifecycle() {
+        let (memory, raw) = boxed_region();
+        let mut slab = unsafe { Slab::new_from_memory(memory, SLAB_SIZE, 64) }.unwrap();
+
+        let ptrs: Vec<_> = (0..slab.stats().capacity).map(|_| slab.allocate().unwrap()).collect();
+        for (i, ptr) in ptrs.iter().enumerate() {
+            unsafe { ptr.as_ptr().write_bytes(i as u8, 64) };
+        }
+        for ptr in ptrs.iter().rev().step_by(2) {
+            slab.deallocate(*ptr);
+        }
+
+        slab.set_hint(CacheHint::Fifo);
+        for ptr in ptrs.iter().step_by(2) {
+            slab.deallocate(*ptr);
+        }
+        #[cfg(feature = "fresh_zeroed")]
+        {
+            let zeroed = slab.allocate_fresh_or_zeroed().unwrap();
+            assert!(slab.is_allocated(zeroed));
+            slab.deallocate(zeroed);
+        }
+
+        let mut upper = slab.split_at(8).unwrap();
+        let ptr = upper.allocate().unwrap();
+        upper.deallocate(ptr);
+        drop(upper);
+        drop(slab);
+
+        drop(unsafe { Box::from_raw(raw) });
+    }
+
+    #[test]
+    fn test_miri_allocator_and_cache() {
+        let mut allocator = SlabAllocator::new(32);
+        let ptrs: Vec<_> = (0..200).map(|_| allocator.allocate().unwrap()).collect();
+        for ptr in ptrs {
+            allocator.deallocate(ptr);
+        }
+
+        let mut cache = SlabCache::new();
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        let ptr = cache.allocate(layout).unwrap();
+        let moved = cache.downgrade(ptr, layout, Layout::from_size_align(16, 8).unwrap()).unwrap();
+        cache.deallocate(moved, Layout::from_size_align(16, 8).unwrap());
     }
 }
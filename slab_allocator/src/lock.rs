@@ -0,0 +1,114 @@
+//! Locks guarding the [`SlabCache`] of a
+//! [`LockedSlabAllocator`](crate::LockedSlabAllocator), so the locking
+//! strategy can be picked per allocator instead of being baked in.
+//!
+//! Constructors are `const` so a lock can sit in a `static`.
+
+use core::cell::UnsafeCell;
+use core::hint;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::SlabCache;
+
+/// Mutual exclusion over a [`SlabCache`]. Dropping the guard unlocks.
+pub trait Lock: Sync {
+    type Guard<'a>: DerefMut<Target = SlabCache>
+    where
+        Self: 'a;
+
+    /// Blocks until the cache is free and hands it out.
+    fn lock(&self) -> Self::Guard<'_>;
+}
+
+/// Busy-waiting lock on an `AtomicBool`, usable without `std`.
+pub struct SpinLock {
+    locked: AtomicBool,
+    cache: UnsafeCell<SlabCache>,
+}
+
+// The cache is only reached through a guard, and one guard exists at a time.
+unsafe impl Sync for SpinLock {}
+
+impl SpinLock {
+    pub const fn new(cache: SlabCache) -> Self {
+        SpinLock {
+            locked: AtomicBool::new(false),
+            cache: UnsafeCell::new(cache),
+        }
+    }
+}
+
+impl Lock for SpinLock {
+    type Guard<'a> = SpinGuard<'a>;
+
+    fn lock(&self) -> SpinGuard<'_> {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            hint::spin_loop();
+        }
+        SpinGuard { lock: self }
+    }
+}
+
+/// Access to the cache of a held [`SpinLock`].
+pub struct SpinGuard<'a> {
+    lock: &'a SpinLock,
+}
+
+impl Deref for SpinGuard<'_> {
+    type Target = SlabCache;
+
+    fn deref(&self) -> &SlabCache {
+        unsafe { &*self.lock.cache.get() }
+    }
+}
+
+impl DerefMut for SpinGuard<'_> {
+    fn deref_mut(&mut self) -> &mut SlabCache {
+        unsafe { &mut *self.lock.cache.get() }
+    }
+}
+
+impl Drop for SpinGuard<'_> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// `std::sync::Mutex`, which parks waiting threads instead of spinning.
+/// A poisoned mutex is entered anyway: the cache stays consistent when a
+/// caller panics while holding it.
+#[cfg(feature = "std")]
+pub struct StdMutexLock {
+    cache: std::sync::Mutex<SlabCache>,
+}
+
+// `SlabCache` is not `Send` only because slabs hold raw pointers into
+// memory they own outright; the mutex serializes every access to it.
+#[cfg(feature = "std")]
+unsafe impl Sync for StdMutexLock {}
+
+#[cfg(feature = "std")]
+impl StdMutexLock {
+    pub const fn new(cache: SlabCache) -> Self {
+        StdMutexLock {
+            cache: std::sync::Mutex::new(cache),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Lock for StdMutexLock {
+    type Guard<'a> = std::sync::MutexGuard<'a, SlabCache>;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        self.cache.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,118 @@
+//! Raw OS calls used by slabs whose backing region is mapped directly from
+//! the kernel instead of coming from the global allocator, or that hand
+//! their pages back to it. Each feature only uses part of these calls.
+#![allow(dead_code)]
+
+use core::ffi::{c_int, c_long, c_uint, c_ulong, c_void};
+use core::ptr::{self, NonNull};
+
+const PROT_NONE: c_int = 0x0;
+const PROT_READ: c_int = 0x1;
+const PROT_WRITE: c_int = 0x2;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+pub(crate) const MAP_HUGETLB: c_int = 0x40000;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+const MADV_DONTNEED: c_int = 4;
+
+const MPOL_PREFERRED: c_int = 1;
+/// Largest node id accepted by `map_on_node`, matching a 1024-bit node mask.
+const MAX_NUMA_NODES: usize = 1024;
+
+#[cfg(target_arch = "x86_64")]
+const SYS_MBIND: c_long = 237;
+#[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+const SYS_MBIND: c_long = 235;
+
+extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: c_long) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+    fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+    fn syscall(number: c_long, ...) -> c_long;
+}
+
+/// # Safety
+/// Maps `len` bytes of anonymous, zero-filled, read-write memory.
+pub(crate) fn map(len: usize, flags: c_int) -> Option<NonNull<u8>> {
+    let ptr = unsafe {
+        mmap(
+            ptr::null_mut(),
+            len,
+            PROT_READ | PROT_WRITE,
+            MAP_PRIVATE | MAP_ANONYMOUS | flags,
+            -1,
+            0,
+        )
+    };
+    if ptr == MAP_FAILED {
+        return None;
+    }
+    NonNull::new(ptr.cast())
+}
+
+/// # Safety
+/// Reserves `len + 2 * guard` bytes with no access rights and opens up the
+/// `len` bytes in the middle, returning their start.
+pub(crate) fn map_guarded(len: usize, guard: usize) -> Option<NonNull<u8>> {
+    let total = len.checked_add(guard.checked_mul(2)?)?;
+    let ptr = unsafe { mmap(ptr::null_mut(), total, PROT_NONE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0) };
+    if ptr == MAP_FAILED {
+        return None;
+    }
+    let memory = unsafe { ptr.cast::<u8>().add(guard) };
+    if unsafe { mprotect(memory.cast(), len, PROT_READ | PROT_WRITE) } != 0 {
+        unsafe { munmap(ptr, total) };
+        return None;
+    }
+    NonNull::new(memory)
+}
+
+/// # Safety
+/// `ptr` and `len` must describe a mapping previously returned by `map`.
+pub(crate) unsafe fn unmap(ptr: NonNull<u8>, len: usize) {
+    munmap(ptr.as_ptr().cast(), len);
+}
+
+/// # Safety
+/// Maps `len` bytes and asks the kernel to prefer `node` for their pages.
+/// The mapping is released again if the policy cannot be applied.
+pub(crate) fn map_on_node(len: usize, node: u32) -> Option<NonNull<u8>> {
+    let node = node as usize;
+    if node >= MAX_NUMA_NODES {
+        return None;
+    }
+
+    let memory = map(len, 0)?;
+    let bits = c_ulong::BITS as usize;
+    let mut mask = [0 as c_ulong; MAX_NUMA_NODES / c_ulong::BITS as usize];
+    mask[node / bits] |= 1 << (node % bits);
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "riscv64"))]
+    let bound = unsafe {
+        syscall(
+            SYS_MBIND,
+            memory.as_ptr(),
+            len,
+            MPOL_PREFERRED,
+            mask.as_ptr(),
+            MAX_NUMA_NODES + 1,
+            0 as c_uint,
+        ) == 0
+    };
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "riscv64")))]
+    let bound = false;
+
+    if !bound {
+        unsafe { unmap(memory, len) };
+        return None;
+    }
+    Some(memory)
+}
+
+/// # Safety
+/// `ptr..ptr + len` must be page-aligned, mapped and hold nothing the caller
+/// still needs: the pages read back as zeroes afterwards.
+pub(crate) unsafe fn discard(ptr: NonNull<u8>, len: usize) -> bool {
+    madvise(ptr.as_ptr().cast(), len, MADV_DONTNEED) == 0
+}
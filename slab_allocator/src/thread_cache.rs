@@ -0,0 +1,187 @@
+//! Per-thread front cache used by `GlobalSlabAllocator` with the
+//! `thread_cache` feature: a few recently freed pointers per size class,
+//! served again without taking the allocator lock.
+//!
+//! Each thread's cache is tagged with the id of the allocator that filled it.
+//! A cache holding pointers of another live allocator is left alone and
+//! bypassed until that allocator drains it, so pointers never cross
+//! allocators. A cache left behind by an allocator that was dropped or reset
+//! is emptied and claimed again.
+//!
+//! When a thread exits, its parked pointers are queued as orphans for their
+//! allocator, which takes them back the next time it locks its cache.
+
+use core::cell::RefCell;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Size classes, in the order of the `SlabCache` tiers.
+pub(crate) const CLASSES: usize = 4;
+/// Pointers kept per class before they are flushed to the shared cache.
+pub(crate) const SLOTS: usize = 8;
+
+struct FrontCache {
+    owner: usize,
+    len: [usize; CLASSES],
+    ptrs: [[*mut u8; SLOTS]; CLASSES],
+}
+
+impl FrontCache {
+    const fn new() -> Self {
+        FrontCache {
+            owner: 0,
+            len: [0; CLASSES],
+            ptrs: [[ptr::null_mut(); SLOTS]; CLASSES],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len.iter().all(|&len| len == 0)
+    }
+
+    /// Whether `owner` may use this cache, claiming it if nobody holds pointers
+    /// in it or if they belong to an allocator that is gone.
+    fn claim(&mut self, owner: usize) -> bool {
+        if self.owner != owner && (self.is_empty() || !is_live(self.owner)) {
+            self.owner = owner;
+            self.len = [0; CLASSES];
+        }
+        self.owner == owner
+    }
+}
+
+impl Drop for FrontCache {
+    /// Queues the parked pointers for their allocator when the thread exits.
+    /// Those of an allocator that is gone pointed into slabs it released, so
+    /// they are dropped.
+    fn drop(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+        let mut registry = registry();
+        if !registry.live.contains(&self.owner) {
+            return;
+        }
+        for class in 0..CLASSES {
+            for &ptr in &self.ptrs[class][..self.len[class]] {
+                registry.orphans.push((self.owner, class, ptr as usize));
+            }
+        }
+        ORPHANS.store(registry.orphans.len(), Ordering::Release);
+    }
+}
+
+extern crate std;
+
+use std::vec::Vec;
+
+/// Allocators that may own parked pointers, and the pointers of exited threads.
+struct Registry {
+    live: Vec<usize>,
+    /// `(owner, class, address)` of each orphaned pointer.
+    orphans: Vec<(usize, usize, usize)>,
+}
+
+static REGISTRY: std::sync::Mutex<Registry> = std::sync::Mutex::new(Registry {
+    live: Vec::new(),
+    orphans: Vec::new(),
+});
+
+/// Length of `Registry::orphans`, read without the lock.
+static ORPHANS: AtomicUsize = AtomicUsize::new(0);
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn is_live(owner: usize) -> bool {
+    registry().live.contains(&owner)
+}
+
+/// Records `owner` as an allocator whose pointers may be parked.
+pub(crate) fn register(owner: usize) {
+    registry().live.push(owner);
+}
+
+/// Forgets `owner` along with its orphans; caches it filled are emptied the
+/// next time they are claimed.
+pub(crate) fn retire(owner: usize) {
+    let mut registry = registry();
+    registry.live.retain(|&live| live != owner);
+    registry.orphans.retain(|&(orphan, _, _)| orphan != owner);
+    ORPHANS.store(registry.orphans.len(), Ordering::Release);
+}
+
+/// Hands every orphan of `owner` and its class to `f`.
+pub(crate) fn adopt(owner: usize, mut f: impl FnMut(usize, NonNull<u8>)) {
+    if ORPHANS.load(Ordering::Acquire) == 0 {
+        return;
+    }
+    let mut registry = registry();
+    registry.orphans.retain(|&(orphan, class, address)| {
+        if orphan != owner {
+            return true;
+        }
+        if let Some(ptr) = NonNull::new(address as *mut u8) {
+            f(class, ptr);
+        }
+        false
+    });
+    ORPHANS.store(registry.orphans.len(), Ordering::Release);
+}
+
+std::thread_local! {
+    static FRONT: RefCell<FrontCache> = const { RefCell::new(FrontCache::new()) };
+}
+
+/// Runs `f` on this thread's cache; `None` while thread-locals are torn down.
+fn with_front<R>(f: impl FnOnce(&mut FrontCache) -> R) -> Option<R> {
+    FRONT.try_with(|front| f(&mut front.borrow_mut())).ok()
+}
+
+/// Pops a cached pointer of `class` filled by `owner`.
+pub(crate) fn pop(owner: usize, class: usize) -> Option<NonNull<u8>> {
+    with_front(|front| {
+        if !front.claim(owner) || front.len[class] == 0 {
+            return None;
+        }
+        front.len[class] -= 1;
+        NonNull::new(front.ptrs[class][front.len[class]])
+    })
+    .flatten()
+}
+
+/// Parks `ptr` in `class`. Returns `false` when the class is full or the
+/// cache belongs to another allocator, in which case the caller frees it.
+pub(crate) fn push(owner: usize, class: usize, ptr: NonNull<u8>) -> bool {
+    with_front(|front| {
+        if !front.claim(owner) || front.len[class] == SLOTS {
+            return false;
+        }
+        front.ptrs[class][front.len[class]] = ptr.as_ptr();
+        front.len[class] += 1;
+        true
+    })
+    .unwrap_or(false)
+}
+
+/// Empties `class`, or every class when `None`, if this thread's cache
+/// belongs to `owner`, handing each pointer and its class to `f`.
+pub(crate) fn drain(owner: usize, class: Option<usize>, mut f: impl FnMut(usize, NonNull<u8>)) {
+    with_front(|front| {
+        if front.owner != owner {
+            return;
+        }
+        for index in 0..CLASSES {
+            if class.is_some_and(|class| class != index) {
+                continue;
+            }
+            for &ptr in &front.ptrs[index][..front.len[index]] {
+                if let Some(ptr) = NonNull::new(ptr) {
+                    f(index, ptr);
+                }
+            }
+            front.len[index] = 0;
+        }
+    });
+}